@@ -0,0 +1,108 @@
+use std::fmt;
+
+use valence_authorization_utils::authorization::{AuthorizationModeInfo, PermissionTypeInfo};
+use valence_program_manager::{
+    account::AccountType, config::Config, program_config::ProgramConfig,
+};
+
+/// Domain where the authorization contract lives, owner and permissioned addresses are on it
+pub const MAIN_DOMAIN: &str = "neutron";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressKind {
+    /// 20 bytes address derived from a public key
+    Wallet,
+    /// 32 bytes address of an instantiated contract
+    Contract,
+}
+
+impl fmt::Display for AddressKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressKind::Wallet => write!(f, "wallet"),
+            AddressKind::Contract => write!(f, "contract"),
+        }
+    }
+}
+
+/// Check the bech32 checksum and prefix of an address, and return if it is a wallet or a contract
+pub fn validate_address(addr: &str, prefix: &str) -> Result<AddressKind, String> {
+    let (hrp, data) = bech32::decode(addr)
+        .map_err(|e| format!("{} is not a valid bech32 address: {}", addr, e))?;
+
+    if hrp.to_lowercase() != prefix {
+        return Err(format!(
+            "{} has prefix {} but {} was expected",
+            addr,
+            hrp.to_lowercase(),
+            prefix
+        ));
+    }
+
+    match data.len() {
+        20 => Ok(AddressKind::Wallet),
+        32 => Ok(AddressKind::Contract),
+        len => Err(format!(
+            "{} has an unexpected length of {} bytes",
+            addr, len
+        )),
+    }
+}
+
+/// Get the bech32 prefix of a domain from `chains.<domain>.prefix` of the manager config
+pub fn get_chain_prefix<'a>(config: &'a Config, domain: &str) -> Result<&'a str, String> {
+    config
+        .chains
+        .get(domain)
+        .map(|chain| chain.prefix.as_str())
+        .ok_or(format!("Chain {} not found in the manager config", domain))
+}
+
+/// Validate an address on a domain using the prefix from the manager config
+pub fn validate_domain_address(
+    config: &Config,
+    addr: &str,
+    domain: &str,
+) -> Result<AddressKind, String> {
+    validate_address(addr, get_chain_prefix(config, domain)?)
+}
+
+/// Validate the addresses given to the builder: owner, permissioned addresses and existing accounts
+pub(crate) fn check_program_addresses(
+    program_config: &ProgramConfig,
+    config: &Config,
+) -> Vec<String> {
+    let mut errors = vec![];
+
+    if let Err(e) = validate_domain_address(config, &program_config.owner, MAIN_DOMAIN) {
+        errors.push(format!("owner: {}", e));
+    }
+
+    for authorization in program_config.authorizations.iter() {
+        let addrs = match &authorization.mode {
+            AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithCallLimit(addrs)) => {
+                addrs.iter().map(|(addr, _)| addr.clone()).collect()
+            }
+            AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithoutCallLimit(addrs)) => {
+                addrs.clone()
+            }
+            AuthorizationModeInfo::Permissionless => vec![],
+        };
+
+        for addr in addrs {
+            if let Err(e) = validate_domain_address(config, &addr, MAIN_DOMAIN) {
+                errors.push(format!("authorization {}: {}", authorization.label, e));
+            }
+        }
+    }
+
+    for account in program_config.accounts.values() {
+        if let AccountType::Addr { addr } = &account.ty {
+            if let Err(e) = validate_domain_address(config, addr, account.domain.get_chain_name()) {
+                errors.push(format!("account {}: {}", account.name, e));
+            }
+        }
+    }
+
+    errors
+}
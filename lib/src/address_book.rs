@@ -0,0 +1,209 @@
+use clap::ValueEnum;
+use std::{collections::BTreeMap, error::Error};
+use valence_program_manager::{account::AccountType, program_config::ProgramConfig};
+
+use crate::address::MAIN_DOMAIN;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum AddressBookFormat {
+    Json,
+    Csv,
+    Dotenv,
+    Toml,
+}
+
+impl AddressBookFormat {
+    pub fn file_name(&self) -> &str {
+        match self {
+            AddressBookFormat::Json => "addresses.json",
+            AddressBookFormat::Csv => "addresses.csv",
+            AddressBookFormat::Dotenv => "addresses.env",
+            AddressBookFormat::Toml => "addresses.toml",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressEntry {
+    pub domain: String,
+    /// `account`, `library`, `authorization` or `processor`
+    pub kind: String,
+    pub label: String,
+    pub address: String,
+}
+
+impl AddressEntry {
+    /// Stable key of the address in its domain, Example: `account_input_account`
+    pub fn key(&self) -> String {
+        if self.kind == self.label {
+            return self.kind.clone();
+        }
+
+        format!("{}_{}", self.kind, to_key(&self.label))
+    }
+}
+
+/// Lowercase alphanumeric key, any other character becomes `_`
+pub(crate) fn to_key(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Flat list of the addresses of an instantiated program, sorted by domain, kind and label,
+/// fails if two labels have the same key once normalized, Example: `A_to_B` and `a-to-b`
+pub fn get_address_book(
+    program_config: &ProgramConfig,
+) -> Result<Vec<AddressEntry>, Box<dyn Error>> {
+    let mut entries = vec![];
+    let mut push = |domain: &str, kind: &str, label: &str, address: Option<&String>| {
+        if let Some(address) = address.filter(|a| !a.is_empty()) {
+            entries.push(AddressEntry {
+                domain: domain.to_string(),
+                kind: kind.to_string(),
+                label: label.to_string(),
+                address: address.clone(),
+            });
+        }
+    };
+
+    for account in program_config.accounts.values() {
+        let address = match &account.ty {
+            AccountType::Addr { addr } => Some(addr),
+            _ => account.addr.as_ref(),
+        };
+        push(
+            account.domain.get_chain_name(),
+            "account",
+            &account.name,
+            address,
+        );
+    }
+
+    for library in program_config.libraries.values() {
+        push(
+            library.domain.get_chain_name(),
+            "library",
+            &library.name,
+            library.addr.as_ref(),
+        );
+    }
+
+    let authorization_data = &program_config.authorization_data;
+    push(
+        MAIN_DOMAIN,
+        "authorization",
+        "authorization",
+        Some(&authorization_data.authorization_addr),
+    );
+
+    // Processors are keyed by domain, Example: `CosmosCosmwasm:neutron`
+    for (domain, addr) in authorization_data.processor_addrs.iter() {
+        let chain = domain.rsplit(':').next().unwrap_or(domain);
+        push(chain, "processor", "processor", Some(addr));
+    }
+
+    entries.sort_by(|a, b| (&a.domain, &a.kind, &a.label).cmp(&(&b.domain, &b.kind, &b.label)));
+
+    // Domains are prefixes of the dotenv keys, so they are normalized too
+    let mut keys: BTreeMap<(String, String), &AddressEntry> = BTreeMap::new();
+    for entry in entries.iter() {
+        if let Some(other) = keys.insert((to_key(&entry.domain), entry.key()), entry) {
+            return Err(format!(
+                "{} {} and {} {} on {} have the same key {} in the address book, rename one of them",
+                other.kind,
+                other.label,
+                entry.kind,
+                entry.label,
+                entry.domain,
+                entry.key()
+            )
+            .into());
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Render the address book of an instantiated program
+pub fn program_address_book(
+    program_config: &ProgramConfig,
+    format: AddressBookFormat,
+) -> Result<String, Box<dyn Error>> {
+    let entries = get_address_book(program_config)?;
+
+    let content = match format {
+        AddressBookFormat::Json => {
+            let mut domains: BTreeMap<&str, BTreeMap<String, &str>> = BTreeMap::new();
+            for entry in entries.iter() {
+                domains
+                    .entry(&entry.domain)
+                    .or_default()
+                    .insert(entry.key(), &entry.address);
+            }
+
+            let mut content =
+                serde_json::to_string_pretty(&domains).expect("Failed to serialize address book");
+            content.push('\n');
+            content
+        }
+        AddressBookFormat::Csv => {
+            let mut content = String::from("domain,kind,label,key,address\n");
+            for entry in entries.iter() {
+                content.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    entry.domain,
+                    entry.kind,
+                    csv_field(&entry.label),
+                    entry.key(),
+                    entry.address
+                ));
+            }
+            content
+        }
+        AddressBookFormat::Dotenv => entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{}_{}={}\n",
+                    to_key(&entry.domain).to_uppercase(),
+                    entry.key().to_uppercase(),
+                    entry.address
+                )
+            })
+            .collect(),
+        AddressBookFormat::Toml => {
+            let mut content = String::new();
+            let mut domain = None;
+            for entry in entries.iter() {
+                if domain != Some(&entry.domain) {
+                    if domain.is_some() {
+                        content.push('\n');
+                    }
+                    content.push_str(&format!("[{}]\n", entry.domain));
+                    domain = Some(&entry.domain);
+                }
+                content.push_str(&format!("{} = \"{}\"\n", entry.key(), entry.address));
+            }
+            content
+        }
+    };
+
+    Ok(content)
+}
+
+/// Quote a csv field if it contains a separator or a quote
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,386 @@
+use config::Config as ConfigHelper;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, error::Error};
+
+use crate::{address::MAIN_DOMAIN, params_schema::ParamsSchema, program_params::ProgramParams};
+
+/// File in the manager config of the environment that holds the asset registry
+const ASSETS_FILE: &str = "assets.toml";
+/// Params prefix that resolves an asset to its denom on a chain, Example: `asset:atom@neutron`
+const ASSET_PARAM_PREFIX: &str = "asset:";
+/// Params prefix for a denom outside the asset registry, Example: `unregistered:ibc/27394...`
+const UNREGISTERED_PARAM_PREFIX: &str = "unregistered:";
+const TRANSFER_PORT: &str = "transfer";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Asset {
+    pub symbol: String,
+    pub base_denom: String,
+    pub origin_chain: String,
+    pub decimals: u32,
+    /// Intermediate chains the asset goes through to reach a chain, the default is a direct transfer
+    #[serde(default)]
+    pub routes: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AssetRegistry {
+    #[serde(default)]
+    pub assets: BTreeMap<String, Asset>,
+    /// Transfer channel ids keyed by chain and counterparty chain
+    #[serde(default)]
+    pub transfer_channels: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Read the asset registry of the environment, an environment without a registry has no assets
+pub fn get_asset_registry(env: &str) -> Result<AssetRegistry, Box<dyn Error>> {
+    let assets_path = std::env::current_dir()?
+        .join("manager_configs")
+        .join(env.to_lowercase())
+        .join(ASSETS_FILE);
+
+    if !assets_path.exists() {
+        return Ok(AssetRegistry::default());
+    }
+
+    ConfigHelper::builder()
+        .add_source(config::File::from(assets_path))
+        .build()?
+        .try_deserialize()
+        .map_err(|e| format!("Failed to parse asset registry : {}", e).into())
+}
+
+/// IBC denom of a denom trace, Example: `transfer/channel-1/uatom` -> `ibc/C4CF...`
+pub fn ibc_denom(trace: &str) -> String {
+    format!(
+        "ibc/{}",
+        hex::encode_upper(Sha256::digest(trace.as_bytes()))
+    )
+}
+
+impl AssetRegistry {
+    pub fn get(&self, name: &str) -> Result<&Asset, String> {
+        self.assets
+            .get(name)
+            .ok_or(format!("Asset {} not found in the asset registry", name))
+    }
+
+    /// Find an asset by its symbol, Example: `ATOM`
+    pub fn get_by_symbol(&self, symbol: &str) -> Result<&Asset, String> {
+        self.assets
+            .values()
+            .find(|asset| asset.symbol.eq_ignore_ascii_case(symbol))
+            .ok_or(format!(
+                "Asset with symbol {} not found in the asset registry",
+                symbol
+            ))
+    }
+
+    fn get_channel(&self, chain: &str, counterparty: &str) -> Result<&str, String> {
+        self.transfer_channels
+            .get(chain)
+            .and_then(|channels| channels.get(counterparty))
+            .map(|channel| channel.as_str())
+            .ok_or(format!(
+                "Transfer channel from {} to {} not found in the asset registry",
+                chain, counterparty
+            ))
+    }
+
+    /// Compute the denom of an asset on a chain from the transfer channels of its route
+    pub fn denom_on(&self, asset: &Asset, chain: &str) -> Result<String, String> {
+        if asset.origin_chain == chain {
+            return Ok(asset.base_denom.clone());
+        }
+
+        let mut hops = vec![asset.origin_chain.as_str()];
+        hops.extend(
+            asset
+                .routes
+                .get(chain)
+                .map(|route| route.iter().map(|c| c.as_str()).collect::<Vec<_>>())
+                .unwrap_or_default(),
+        );
+        hops.push(chain);
+
+        // The channel of the last hop comes first in the denom trace
+        let mut trace = asset.base_denom.clone();
+        for pair in hops.windows(2) {
+            let channel = self.get_channel(pair[1], pair[0])?;
+            trace = format!("{}/{}/{}", TRANSFER_PORT, channel, trace);
+        }
+
+        Ok(ibc_denom(&trace))
+    }
+
+    /// Resolve an asset reference, Example: `atom@neutron`
+    pub fn resolve(&self, reference: &str) -> Result<String, String> {
+        let (name, chain) = reference.split_once('@').ok_or(format!(
+            "Invalid asset reference {}, expected asset@chain",
+            reference
+        ))?;
+
+        self.denom_on(self.get(name)?, chain)
+    }
+
+    /// Find which asset an ibc denom on a chain belongs to
+    pub fn find_ibc_denom_on(&self, denom: &str, chain: &str) -> Option<&str> {
+        self.assets
+            .iter()
+            .find(|(_, asset)| self.denom_on(asset, chain).is_ok_and(|d| d == denom))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Find which asset and chain an ibc denom belongs to
+    pub fn find_ibc_denom(&self, denom: &str) -> Option<(&str, &str)> {
+        self.assets.iter().find_map(|(name, asset)| {
+            self.transfer_channels
+                .keys()
+                .find(|chain| self.denom_on(asset, chain).is_ok_and(|d| d == denom))
+                .map(|chain| (name.as_str(), chain.as_str()))
+        })
+    }
+}
+
+/// Parse an amount in base units, either raw (`1000000`) or with an asset symbol (`0.5 ATOM`)
+/// converted exactly with the decimals of the asset, returns the asset if a symbol was used
+pub fn parse_amount<'a>(
+    value: &str,
+    registry: &'a AssetRegistry,
+) -> Result<(u128, Option<&'a Asset>), String> {
+    let value = value.trim();
+
+    let Some((number, symbol)) = value.split_once(char::is_whitespace) else {
+        return value
+            .parse::<u128>()
+            .map(|amount| (amount, None))
+            .map_err(|_| {
+                format!(
+                    "Invalid amount {:?}, expected an integer or \"<amount> <SYMBOL>\"",
+                    value
+                )
+            });
+    };
+
+    let asset = registry.get_by_symbol(symbol.trim())?;
+    let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+
+    if int.is_empty()
+        || !int.chars().all(|c| c.is_ascii_digit())
+        || !frac.chars().all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("Invalid amount {:?}", value));
+    }
+
+    // Digits beyond the decimals of the asset can't be represented in base units
+    let decimals = asset.decimals as usize;
+    if frac.trim_end_matches('0').len() > decimals {
+        return Err(format!(
+            "Amount {:?} has more than {} decimals, precision would be lost",
+            value, decimals
+        ));
+    }
+
+    let frac = format!("{:0<width$}", frac, width = decimals);
+    let amount = format!("{}{}", int, &frac[..decimals])
+        .parse::<u128>()
+        .map_err(|_| format!("Amount {:?} is too large", value))?;
+
+    Ok((amount, Some(asset)))
+}
+
+/// Resolve `asset:` params into denoms, and check that `ibc/` params are the denom of an asset of
+/// the registry on the domain of the param, unless they are marked `unregistered:` or the
+/// environment has no registry
+pub(crate) fn resolve_asset_params(
+    params: &mut ProgramParams,
+    registry: &AssetRegistry,
+    schema: Option<&ParamsSchema>,
+) -> Result<(), Box<dyn Error>> {
+    for (key, value) in params.to_sorted_map() {
+        if let Some(reference) = value.strip_prefix(ASSET_PARAM_PREFIX) {
+            let denom = registry
+                .resolve(reference)
+                .map_err(|e| format!("Param {}: {}", key, e))?;
+            params.set(&key, &denom);
+        } else if let Some(denom) = value.strip_prefix(UNREGISTERED_PARAM_PREFIX) {
+            params.set(&key, denom);
+        } else if value.starts_with("ibc/") && !registry.assets.is_empty() {
+            let domain = schema.map_or(MAIN_DOMAIN, |schema| schema.domain(&key));
+            if registry.find_ibc_denom_on(&value, domain).is_some() {
+                continue;
+            }
+
+            // The denom of an asset on another chain is a likely mistake
+            let hint = match registry.find_ibc_denom(&value) {
+                Some((asset, chain)) => format!(", it is the denom of {} on {}", asset, chain),
+                None => format!(
+                    ", use {}{} if it is not in the registry",
+                    UNREGISTERED_PARAM_PREFIX, value
+                ),
+            };
+            return Err(format!(
+                "Param {}: {} does not match any asset of the asset registry on {}{}",
+                key, value, domain, hint
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{ibc_denom, parse_amount, Asset, AssetRegistry};
+
+    const ATOM_ON_NEUTRON: &str =
+        "ibc/C4CFF46FD6DE35CA4CF4CE031E643C8FDC9BA4B99AE598E9B0ED98FE3A2319F9";
+    const ATOM_ON_OSMOSIS: &str =
+        "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+    fn asset(symbol: &str, base_denom: &str, origin_chain: &str) -> Asset {
+        Asset {
+            symbol: symbol.to_string(),
+            base_denom: base_denom.to_string(),
+            origin_chain: origin_chain.to_string(),
+            decimals: 6,
+            routes: BTreeMap::new(),
+        }
+    }
+
+    fn registry() -> AssetRegistry {
+        AssetRegistry {
+            assets: BTreeMap::from([
+                ("atom".to_string(), asset("ATOM", "uatom", "cosmoshub")),
+                ("osmo".to_string(), asset("OSMO", "uosmo", "osmosis")),
+            ]),
+            transfer_channels: BTreeMap::from([
+                (
+                    "neutron".to_string(),
+                    BTreeMap::from([("cosmoshub".to_string(), "channel-1".to_string())]),
+                ),
+                (
+                    "osmosis".to_string(),
+                    BTreeMap::from([("cosmoshub".to_string(), "channel-0".to_string())]),
+                ),
+                (
+                    "stride".to_string(),
+                    BTreeMap::from([("osmosis".to_string(), "channel-5".to_string())]),
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn ibc_denom_of_trace() {
+        assert_eq!(ibc_denom("transfer/channel-1/uatom"), ATOM_ON_NEUTRON);
+    }
+
+    #[test]
+    fn denom_on_chains() {
+        let registry = registry();
+        let atom = registry.get("atom").unwrap();
+
+        assert_eq!(registry.denom_on(atom, "cosmoshub").unwrap(), "uatom");
+        assert_eq!(registry.denom_on(atom, "neutron").unwrap(), ATOM_ON_NEUTRON);
+        assert_eq!(registry.denom_on(atom, "osmosis").unwrap(), ATOM_ON_OSMOSIS);
+        assert_eq!(registry.resolve("atom@neutron").unwrap(), ATOM_ON_NEUTRON);
+        assert!(registry.denom_on(atom, "juno").is_err());
+    }
+
+    #[test]
+    fn denom_on_chain_through_a_route() {
+        let mut registry = registry();
+        registry
+            .assets
+            .get_mut("atom")
+            .unwrap()
+            .routes
+            .insert("stride".to_string(), vec!["osmosis".to_string()]);
+        let atom = registry.get("atom").unwrap();
+
+        // The channel of the last hop comes first
+        assert_eq!(
+            registry.denom_on(atom, "stride").unwrap(),
+            ibc_denom("transfer/channel-5/transfer/channel-0/uatom")
+        );
+    }
+
+    #[test]
+    fn find_ibc_denom_on_the_domain_of_the_param() {
+        let registry = registry();
+
+        assert_eq!(
+            registry.find_ibc_denom_on(ATOM_ON_NEUTRON, "neutron"),
+            Some("atom")
+        );
+        // The denom of ATOM on Osmosis is not a Neutron denom
+        assert_eq!(registry.find_ibc_denom_on(ATOM_ON_OSMOSIS, "neutron"), None);
+        assert_eq!(
+            registry.find_ibc_denom(ATOM_ON_OSMOSIS),
+            Some(("atom", "osmosis"))
+        );
+    }
+
+    #[test]
+    fn parse_amount_with_symbol() {
+        let registry = registry();
+
+        let (amount, asset) = parse_amount("0.5 ATOM", &registry).unwrap();
+        assert_eq!(amount, 500_000);
+        assert_eq!(asset.unwrap().base_denom, "uatom");
+
+        let (amount, asset) = parse_amount("1000 OSMO", &registry).unwrap();
+        assert_eq!(amount, 1_000_000_000);
+        assert_eq!(asset.unwrap().base_denom, "uosmo");
+    }
+
+    #[test]
+    fn parse_raw_amount() {
+        assert_eq!(parse_amount("1000000", &registry()).unwrap().0, 1_000_000);
+        assert!(parse_amount("1000000", &registry()).unwrap().1.is_none());
+    }
+
+    #[test]
+    fn parse_amount_with_trailing_zeros_beyond_decimals() {
+        let (amount, _) = parse_amount("1.50000000 ATOM", &registry()).unwrap();
+        assert_eq!(amount, 1_500_000);
+    }
+
+    #[test]
+    fn parse_amount_with_too_many_decimals() {
+        let error = parse_amount("0.0000001 ATOM", &registry()).unwrap_err();
+        assert!(error.contains("more than 6 decimals"), "{}", error);
+    }
+
+    #[test]
+    fn parse_amount_with_unknown_symbol() {
+        let error = parse_amount("1 FOO", &registry()).unwrap_err();
+        assert!(error.contains("FOO not found"), "{}", error);
+    }
+
+    #[test]
+    fn parse_amount_overflow() {
+        // u128::MAX in base units fits, one more unit of the asset does not
+        assert_eq!(
+            parse_amount(&u128::MAX.to_string(), &registry()).unwrap().0,
+            u128::MAX
+        );
+        assert!(parse_amount("340282366920938463463374607431768211456", &registry()).is_err());
+
+        let error =
+            parse_amount("340282366920938463463374607431768211455 ATOM", &registry()).unwrap_err();
+        assert!(error.contains("too large"), "{}", error);
+    }
+
+    #[test]
+    fn parse_invalid_amount() {
+        assert!(parse_amount("1,5 ATOM", &registry()).is_err());
+        assert!(parse_amount(".5 ATOM", &registry()).is_err());
+        assert!(parse_amount("-1", &registry()).is_err());
+    }
+}
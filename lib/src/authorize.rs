@@ -0,0 +1,151 @@
+use std::{error::Error, ops::Deref};
+use valence_authorization_utils::{
+    authorization::{AuthorizationModeInfo, PermissionTypeInfo},
+    authorization_message::MessageDetails,
+    builders::{AtomicFunctionBuilder, AtomicSubroutineBuilder, AuthorizationBuilder},
+    domain::Domain,
+};
+use valence_library_utils::LibraryAccountType;
+use valence_program_manager::{
+    domain::Domain as ProgramDomain, library::LibraryInfo,
+    program_config_builder::ProgramConfigBuilder,
+};
+
+use crate::{
+    address::MAIN_DOMAIN,
+    messages::{process_function_message, LibraryFunction},
+};
+
+/// A library added to the program builder, with the domain it is deployed on
+/// Derefs to its `LibraryAccountType` so it can be linked like any other library
+#[derive(Debug, Clone)]
+pub struct ProgramLibrary {
+    id: LibraryAccountType,
+    domain: ProgramDomain,
+}
+
+impl Deref for ProgramLibrary {
+    type Target = LibraryAccountType;
+
+    fn deref(&self) -> &Self::Target {
+        &self.id
+    }
+}
+
+impl ProgramLibrary {
+    /// Domain of the authorization functions that call this library
+    pub fn authorization_domain(&self) -> Domain {
+        match self.domain.get_chain_name() {
+            MAIN_DOMAIN => Domain::Main,
+            chain => Domain::External(chain.to_string()),
+        }
+    }
+}
+
+/// Add a library to the program builder, keeping its domain for `authorize`
+pub fn add_library(builder: &mut ProgramConfigBuilder, library: LibraryInfo) -> ProgramLibrary {
+    let domain = library.domain.clone();
+
+    ProgramLibrary {
+        id: builder.add_library(library),
+        domain,
+    }
+}
+
+/// An authorization that executes a single function of a library, permissionless unless set otherwise
+#[must_use = "the authorization is only added to the builder by add()"]
+pub struct Authorize<'a> {
+    builder: &'a mut ProgramConfigBuilder,
+    label: String,
+    library: ProgramLibrary,
+    message: Result<MessageDetails, Box<dyn Error>>,
+}
+
+/// Authorize a function of a library, the authorization is added to the builder by `add`
+/// Example: `authorize(&mut builder, "forward", &forwarder, FunctionMsgs::Forward {}).permissioned(addrs).with_call_limit(1).add()`
+pub fn authorize<'a, T: LibraryFunction>(
+    builder: &'a mut ProgramConfigBuilder,
+    label: &str,
+    library: &ProgramLibrary,
+    function: T,
+) -> Authorize<'a> {
+    Authorize {
+        builder,
+        label: label.to_string(),
+        library: library.clone(),
+        message: process_function_message(&function),
+    }
+}
+
+impl<'a> Authorize<'a> {
+    /// Only these addresses can execute the authorization
+    pub fn permissioned(self, addrs: Vec<String>) -> PermissionedAuthorize<'a> {
+        PermissionedAuthorize {
+            authorize: self,
+            addrs,
+            call_limit: None,
+        }
+    }
+
+    /// Add the permissionless authorization to the builder
+    pub fn add(self) -> Result<(), Box<dyn Error>> {
+        self.add_with_mode(AuthorizationModeInfo::Permissionless)
+    }
+
+    fn add_with_mode(self, mode: AuthorizationModeInfo) -> Result<(), Box<dyn Error>> {
+        let message = self
+            .message
+            .map_err(|e| format!("Authorization {} : {}", self.label, e))?;
+        let function = AtomicFunctionBuilder::new()
+            .with_contract_address(self.library.id.clone())
+            .with_domain(self.library.authorization_domain())
+            .with_message_details(message)
+            .build();
+
+        self.builder.add_authorization(
+            AuthorizationBuilder::new()
+                .with_label(&self.label)
+                .with_mode(mode)
+                .with_subroutine(
+                    AtomicSubroutineBuilder::new()
+                        .with_function(function)
+                        .build(),
+                )
+                .build(),
+        );
+
+        Ok(())
+    }
+}
+
+/// An authorization only the permissioned addresses can execute, without call limit unless set
+#[must_use = "the authorization is only added to the builder by add()"]
+pub struct PermissionedAuthorize<'a> {
+    authorize: Authorize<'a>,
+    addrs: Vec<String>,
+    call_limit: Option<u128>,
+}
+
+impl PermissionedAuthorize<'_> {
+    /// Limit the permissioned addresses to a number of calls each
+    pub fn with_call_limit(mut self, limit: u128) -> Self {
+        self.call_limit = Some(limit);
+        self
+    }
+
+    /// Add the permissioned authorization to the builder
+    pub fn add(self) -> Result<(), Box<dyn Error>> {
+        let permission = match self.call_limit {
+            Some(limit) => PermissionTypeInfo::WithCallLimit(
+                self.addrs
+                    .into_iter()
+                    .map(|addr| (addr, limit.into()))
+                    .collect(),
+            ),
+            None => PermissionTypeInfo::WithoutCallLimit(self.addrs),
+        };
+
+        self.authorize
+            .add_with_mode(AuthorizationModeInfo::Permissioned(permission))
+    }
+}
@@ -0,0 +1,408 @@
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use valence_authorization_utils::authorization_message::ParamRestriction;
+use valence_program_manager::program_config::ProgramConfig;
+
+use crate::{
+    address_book::{get_address_book, to_key},
+    invocation::{
+        decode_restriction_value, get_invocations, AuthorizationInvocation, FunctionInvocation,
+    },
+    messages::PROCESS_FUNCTION,
+    program_config::to_snake_case,
+    restrictions::is_known_library,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum CodegenLanguage {
+    Rust,
+    Typescript,
+}
+
+/// `provide_liquidity` -> `ProvideLiquidity`, `A_to_B` -> `AToB`
+fn to_pascal_case(name: &str) -> String {
+    let pascal = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+
+    // Identifiers can't start with a digit
+    if pascal.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", pascal)
+    } else {
+        pascal
+    }
+}
+
+/// Argument of a generated constructor for a message and the expression of the message,
+/// typed with the `FunctionMsgs` of the library when we know it
+fn message_arg(function: &FunctionInvocation, arg: &str) -> (String, String) {
+    let name = function
+        .message
+        .as_object()
+        .and_then(|message| message.keys().next())
+        .cloned()
+        .unwrap_or_default();
+
+    let ty = match function.library.as_deref() {
+        Some(library) if name == PROCESS_FUNCTION && is_known_library(library) => {
+            format!("{}::msg::FunctionMsgs", library)
+        }
+        _ => "serde_json::Value".to_string(),
+    };
+
+    (
+        format!("{}: {}", arg, ty),
+        format!(
+            "serde_json::json!({{ {:?}: serde_json::to_value({})? }})",
+            name, arg
+        ),
+    )
+}
+
+/// Checks of the generated code for the restrictions of the authorization on a message
+fn restriction_checks(function: &FunctionInvocation, index: usize) -> String {
+    function
+        .restrictions
+        .iter()
+        .map(|restriction| {
+            let (path, included, value) = match restriction {
+                ParamRestriction::MustBeIncluded(path) => (path, true, None),
+                ParamRestriction::CannotBeIncluded(path) => (path, false, None),
+                ParamRestriction::MustBeValue(path, value) => (
+                    path,
+                    true,
+                    Some(decode_restriction_value(value).to_string()),
+                ),
+            };
+
+            format!(
+                "        Self::check_field(&messages[{}], &{:?}, {}, {:?})?;\n",
+                index, path, included, value
+            )
+        })
+        .collect()
+}
+
+/// Restriction check of the generated authorization enum, the expected value is JSON
+const RUST_CLIENT_CHECK_FIELD: &str = r#"    fn check_field(
+        message: &serde_json::Value,
+        path: &[&str],
+        included: bool,
+        value: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let field = path.iter().try_fold(message, |message, key| message.get(key));
+
+        match (field, included, value) {
+            (None, true, _) => Err(format!("{} must be included", path.join(".")).into()),
+            (Some(_), false, _) => Err(format!("{} cannot be included", path.join(".")).into()),
+            (Some(field), true, Some(value)) if *field != serde_json::from_str::<serde_json::Value>(value)? => {
+                Err(format!("{} must be {}", path.join("."), value).into())
+            }
+            _ => Ok(()),
+        }
+    }
+"#;
+
+/// `send_msgs` of the generated authorization enum
+const RUST_CLIENT_SEND_MSGS: &str = r#"    /// `send_msgs` ExecuteMsg of the authorization contract that triggers the authorization with the messages,
+    /// Example: `Self::Forward.send_msgs(&Self::forward_messages(FunctionMsgs::Forward {})?)`
+    pub fn send_msgs(
+        &self,
+        messages: &[serde_json::Value],
+    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        if messages.len() != self.contract_addresses().len() {
+            return Err(format!(
+                "{} expects {} messages, got {}",
+                self.label(),
+                self.contract_addresses().len(),
+                messages.len()
+            )
+            .into());
+        }
+
+        let messages = messages
+            .iter()
+            .map(|message| {
+                Ok(serde_json::json!({
+                    "cosmwasm_execute_msg": { "msg": cosmwasm_std::to_json_binary(message)? }
+                }))
+            })
+            .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+        Ok(serde_json::json!({
+            "permissionless_action": {
+                "send_msgs": { "label": self.label(), "messages": messages, "ttl": null }
+            }
+        }))
+    }
+"#;
+
+/// Rust module with the addresses and authorizations of a deployed program,
+/// keepers can depend on it instead of copying strings,
+/// fails if two labels have the same name once normalized, Example: `A_to_B` and `a-to-b`
+pub fn rust_client(
+    program: &str,
+    env: &str,
+    program_config: &ProgramConfig,
+) -> Result<String, Box<dyn Error>> {
+    let name = to_pascal_case(&format!("{}_{}", program, env));
+    let authorization_enum = format!("{}Authorization", name);
+    let invocations = get_invocations(program_config);
+
+    let mut variants: Vec<(String, &AuthorizationInvocation)> = vec![];
+    for invocation in invocations.iter() {
+        let variant = to_pascal_case(&invocation.label);
+        if variant.is_empty() {
+            return Err(format!(
+                "Label {} can't be used in the Rust client, it has no alphanumeric character",
+                invocation.label
+            )
+            .into());
+        }
+        if let Some((_, other)) = variants.iter().find(|(v, _)| *v == variant) {
+            return Err(format!(
+                "Labels {} and {} are both {} in the Rust client, rename one of them",
+                other.label, invocation.label, variant
+            )
+            .into());
+        }
+        variants.push((variant, invocation));
+    }
+
+    let mut code = format!(
+        "// Generated by deployer-lib from the {program} deployment on {env}, do not edit\n\n\
+         /// Addresses of {program} deployed on {env}, by domain\n\
+         pub struct {name};\n\n\
+         impl {name} {{\n    pub const PROGRAM_ID: u64 = {id};\n    pub const ENV: &'static str = {env:?};\n",
+        id = program_config.id,
+    );
+
+    for entry in get_address_book(program_config)? {
+        code.push_str(&format!(
+            "    pub const {}_{}: &'static str = {:?};\n",
+            to_key(&entry.domain).to_uppercase(),
+            entry.key().to_uppercase(),
+            entry.address
+        ));
+    }
+    code.push_str("}\n\n");
+
+    code.push_str(&format!(
+        "/// Authorizations of {program} on {env}\n\
+         #[derive(Debug, Clone, Copy, PartialEq, Eq)]\n\
+         pub enum {authorization_enum} {{\n"
+    ));
+    for (variant, _) in variants.iter() {
+        code.push_str(&format!("    {},\n", variant));
+    }
+    code.push_str("}\n\n");
+
+    code.push_str(&format!(
+        "impl {authorization_enum} {{\n    pub const ALL: &'static [Self] = &[\n"
+    ));
+    for (variant, _) in variants.iter() {
+        code.push_str(&format!("        Self::{},\n", variant));
+    }
+    code.push_str("    ];\n\n");
+
+    let mut push_match =
+        |doc: &str, signature: &str, value: &dyn Fn(&AuthorizationInvocation) -> String| {
+            code.push_str(&format!(
+                "    /// {}\n    pub const fn {} {{\n        match *self {{\n",
+                doc, signature
+            ));
+            for (variant, invocation) in variants.iter() {
+                code.push_str(&format!(
+                    "            Self::{} => {},\n",
+                    variant,
+                    value(invocation)
+                ));
+            }
+            code.push_str("        }\n    }\n\n");
+        };
+
+    push_match(
+        "Label of the authorization in the authorization contract",
+        "label(&self) -> &'static str",
+        &|invocation| format!("{:?}", invocation.label),
+    );
+    push_match(
+        "Contract each message is executed on, in the same order as the messages",
+        "contract_addresses(&self) -> &'static [&'static str]",
+        &|invocation| {
+            let addrs = invocation
+                .functions
+                .iter()
+                .map(|function| {
+                    format!(
+                        "{:?}",
+                        function.contract_address.as_deref().unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("&[{}]", addrs.join(", "))
+        },
+    );
+
+    for (variant, invocation) in variants.iter() {
+        let mut args = vec![];
+        let mut messages = vec![];
+        let mut checks = String::new();
+        for (i, function) in invocation.functions.iter().enumerate() {
+            let arg = match invocation.functions.len() {
+                1 => "function".to_string(),
+                _ => format!("function{}", i),
+            };
+            let (arg, message) = message_arg(function, &arg);
+            args.push(arg);
+            messages.push(message);
+            checks.push_str(&restriction_checks(function, i));
+        }
+
+        code.push_str(&format!(
+            "    /// Messages of the {label} authorization, in the same order as the contract addresses,\n    \
+             /// fails if they don't satisfy the restrictions of the authorization\n    \
+             pub fn {fn_name}_messages(\n        {args},\n    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {{\n        \
+             let messages = vec![\n            {messages},\n        ];\n{checks}\n        Ok(messages)\n    }}\n\n",
+            label = invocation.label,
+            fn_name = to_snake_case(variant),
+            args = args.join(",\n        "),
+            messages = messages.join(",\n            "),
+        ));
+    }
+
+    code.push_str(RUST_CLIENT_SEND_MSGS);
+
+    // Only generated if an authorization has restrictions, it would be dead code otherwise
+    if invocations.iter().any(|invocation| {
+        invocation
+            .functions
+            .iter()
+            .any(|f| !f.restrictions.is_empty())
+    }) {
+        code.push('\n');
+        code.push_str(RUST_CLIENT_CHECK_FIELD);
+    }
+    code.push_str("}\n");
+
+    Ok(code)
+}
+
+/// `astroport_lper` -> `astroportLper`
+fn to_camel_case(name: &str) -> String {
+    let pascal = to_pascal_case(name);
+    let mut chars = pascal.chars();
+
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// Addresses and authorizations of a deployed program, as a JSON value for the TypeScript client
+fn get_client_data(program_config: &ProgramConfig) -> Result<Value, Box<dyn Error>> {
+    let mut addresses: Map<String, Value> = Map::new();
+    for entry in get_address_book(program_config)? {
+        addresses
+            .entry(entry.domain.clone())
+            .or_insert(Value::Object(Map::new()))
+            .as_object_mut()
+            .expect("Domain addresses should be an object")
+            .insert(entry.key(), Value::String(entry.address));
+    }
+
+    let authorizations = get_invocations(program_config)
+        .into_iter()
+        .map(|invocation| {
+            let messages = invocation
+                .functions
+                .iter()
+                .map(|function| {
+                    json!({
+                        "domain": function.domain,
+                        "contractAddress": function.contract_address,
+                        "msg": function.message,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            (
+                invocation.label.clone(),
+                json!({
+                    "permissionless": invocation.permissionless,
+                    "messages": messages,
+                    "sendMsgs": invocation.send_msgs(),
+                }),
+            )
+        })
+        .collect::<Map<_, _>>();
+
+    Ok(json!({
+        "programId": program_config.id,
+        "addresses": addresses,
+        "authorizations": authorizations,
+    }))
+}
+
+/// TypeScript module with the addresses and authorizations of a program, keyed by env
+pub fn typescript_client(
+    program: &str,
+    deployments: &[(String, ProgramConfig)],
+) -> Result<String, Box<dyn Error>> {
+    let name = to_camel_case(program);
+    let type_name = to_pascal_case(program);
+
+    let data = deployments
+        .iter()
+        .map(|(env, program_config)| Ok((env.clone(), get_client_data(program_config)?)))
+        .collect::<Result<Map<_, _>, Box<dyn Error>>>()?;
+
+    Ok(format!(
+        r#"// Generated by deployer-lib from the {program} deployments, do not edit
+
+/** `send_msgs` ExecuteMsg of the authorization contract, the messages are JSON encoded in base64 */
+export type SendMsgs<L extends string = string> = {{
+  permissionless_action: {{
+    send_msgs: {{
+      label: L;
+      messages: {{ cosmwasm_execute_msg: {{ msg: string }} }}[];
+      ttl: unknown;
+    }};
+  }};
+}};
+
+/** Addresses by domain and authorizations of {program}, by env */
+export const {name} = {data} as const;
+
+export type {type_name}Env = keyof typeof {name};
+
+/** Authorization labels of {program}, by env */
+export type {type_name}AuthorizationLabels = {{
+  [E in {type_name}Env]: keyof (typeof {name})[E]["authorizations"];
+}};
+
+/** Messages each authorization of {program} permits, by env and label, fields not set by its restrictions are left to fill */
+export type {type_name}Messages = {{
+  [E in {type_name}Env]: {{
+    [L in keyof (typeof {name})[E]["authorizations"]]: (typeof {name})[E]["authorizations"][L] extends {{
+      messages: readonly (infer M)[];
+    }}
+      ? M extends {{ msg: infer T }}
+        ? T
+        : never
+      : never;
+  }};
+}};
+"#,
+        data = serde_json::to_string_pretty(&data)?,
+    ))
+}
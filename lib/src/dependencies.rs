@@ -0,0 +1,132 @@
+use std::{collections::BTreeSet, error::Error, path::PathBuf, process::Command};
+
+use crate::{
+    program_params::{get_program_file_params, ENV_PARAMS_PREFIX},
+    references::ProgramReference,
+};
+
+/// A program deployed on an environment
+pub type ProgramNode = (String, String);
+
+/// Programs (and their env) a program depends on, declared by the `ref:` params of the env files
+pub fn get_program_dependencies(
+    program: &str,
+    env: &str,
+) -> Result<BTreeSet<ProgramNode>, Box<dyn Error>> {
+    let program_path = PathBuf::from("programs").join(program);
+
+    if !program_path.exists() {
+        return Err(format!("Program {} does not exist in the workspace", program).into());
+    }
+
+    let params = get_program_file_params(&program_path, env)?;
+    let mut dependencies = BTreeSet::new();
+
+    for (key, value) in params.to_sorted_map() {
+        if let Some(reference) = ProgramReference::parse(&value) {
+            let reference = reference.map_err(|e| format!("{} param {}: {}", program, key, e))?;
+            dependencies.insert((reference.program, reference.env));
+        }
+    }
+
+    Ok(dependencies)
+}
+
+/// Topological order of the program and all its dependencies, dependencies come first
+pub fn get_deployment_order(program: &str, env: &str) -> Result<Vec<ProgramNode>, Box<dyn Error>> {
+    let mut order = vec![];
+    let mut visiting = vec![];
+
+    visit(
+        (program.to_string(), env.to_string()),
+        &mut visiting,
+        &mut order,
+    )?;
+
+    Ok(order)
+}
+
+fn visit(
+    node: ProgramNode,
+    visiting: &mut Vec<ProgramNode>,
+    order: &mut Vec<ProgramNode>,
+) -> Result<(), Box<dyn Error>> {
+    if order.contains(&node) {
+        return Ok(());
+    }
+
+    if let Some(start) = visiting.iter().position(|n| *n == node) {
+        let cycle = visiting[start..]
+            .iter()
+            .chain(std::iter::once(&node))
+            .map(|(program, env)| format!("{}@{}", program, env))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        return Err(format!("Programs depend on each other in a cycle: {}", cycle).into());
+    }
+
+    visiting.push(node.clone());
+    for dependency in get_program_dependencies(&node.0, &node.1)? {
+        visit(dependency, visiting, order)?;
+    }
+    visiting.pop();
+
+    order.push(node);
+
+    Ok(())
+}
+
+/// Deploy the dependencies of a program in order, each one with its own deployer
+/// so its instantiated addresses are in its output before its dependents resolve them,
+/// dependencies already deployed with the same config are skipped,
+/// fails before deploying anything if a dependency is on another env
+pub(crate) fn deploy_dependencies(program: &str, env: &str) -> Result<(), Box<dyn Error>> {
+    let root = (program.to_string(), env.to_string());
+    let order = get_deployment_order(program, env)?;
+
+    // Deploying on the target env must not deploy anything on another env as a side effect
+    let other_envs = order
+        .iter()
+        .filter(|(_, dependency_env)| dependency_env != env)
+        .map(|(dependency, dependency_env)| format!("{}@{}", dependency, dependency_env))
+        .collect::<Vec<_>>();
+    if !other_envs.is_empty() {
+        return Err(format!(
+            "Dependencies on another env than {} are not deployed with --with-dependencies, deploy them on their env first: {}",
+            env,
+            other_envs.join(", ")
+        )
+        .into());
+    }
+
+    for (dependency, dependency_env) in order.iter().filter(|node| **node != root) {
+        println!("Deploying dependency {} on {}", dependency, dependency_env);
+
+        let manifest_path = PathBuf::from("programs")
+            .join(dependency)
+            .join("Cargo.toml");
+
+        let mut command = Command::new("cargo");
+        command
+            .arg("run")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .args(["--", "--target-env", dependency_env, "--skip-existing"]);
+
+        // Param overrides from the env are meant for the program being deployed
+        for (key, _) in std::env::vars() {
+            if key.starts_with(&format!("{}_", ENV_PARAMS_PREFIX)) {
+                command.env_remove(key);
+            }
+        }
+
+        let status = command
+            .status()
+            .map_err(|e| format!("Failed to deploy dependency {}: {}", dependency, e))?;
+        if !status.success() {
+            return Err(format!("Failed to deploy dependency {}: {}", dependency, status).into());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use valence_authorization_utils::authorization::{
+    AuthorizationInfo, AuthorizationModeInfo, PermissionTypeInfo,
+};
+use valence_library_utils::LibraryAccountType;
+use valence_program_manager::program_config::ProgramConfig;
+
+use crate::program_config::{get_authorization_functions, get_variant_name};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DiagramFormat {
+    Mermaid,
+    Dot,
+}
+
+/// A node of the diagram, accounts and libraries are grouped by domain
+struct Node {
+    id: String,
+    label: String,
+    is_account: bool,
+}
+
+/// An edge of the diagram, dashed edges are authorizations
+struct Edge {
+    from: String,
+    to: String,
+    label: String,
+    dashed: bool,
+}
+
+struct Graph {
+    clusters: BTreeMap<String, Vec<Node>>,
+    authorizations: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+/// Render a program config (raw or instantiated) as a diagram
+pub fn program_diagram(program_config: &ProgramConfig, format: DiagramFormat) -> String {
+    let graph = build_graph(program_config);
+
+    match format {
+        DiagramFormat::Mermaid => to_mermaid(&graph),
+        DiagramFormat::Dot => to_dot(&graph, &program_config.name),
+    }
+}
+
+fn authorization_mode(authorization: &AuthorizationInfo) -> String {
+    match &authorization.mode {
+        AuthorizationModeInfo::Permissionless => "permissionless".to_string(),
+        AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithCallLimit(addrs)) => {
+            format!("permissioned, {} with call limit", addrs.len())
+        }
+        AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithoutCallLimit(addrs)) => {
+            format!("permissioned, {} without call limit", addrs.len())
+        }
+    }
+}
+
+fn build_graph(program_config: &ProgramConfig) -> Graph {
+    let mut clusters: BTreeMap<String, Vec<Node>> = BTreeMap::new();
+    let mut edges = vec![];
+
+    for (id, account) in program_config.accounts.iter() {
+        clusters
+            .entry(account.domain.get_chain_name().to_string())
+            .or_default()
+            .push(Node {
+                id: format!("account_{}", id),
+                label: [Some(account.name.clone()), account.addr.clone()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                is_account: true,
+            });
+    }
+
+    for (id, library) in program_config.libraries.iter() {
+        clusters
+            .entry(library.domain.get_chain_name().to_string())
+            .or_default()
+            .push(Node {
+                id: format!("library_{}", id),
+                label: [
+                    Some(library.name.clone()),
+                    get_variant_name(&library.config),
+                    library.addr.clone(),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join("\n"),
+                is_account: false,
+            });
+    }
+
+    for link in program_config.links.values() {
+        let library = format!("library_{}", link.library_id);
+
+        for account_id in link.input_accounts_id.iter() {
+            edges.push(Edge {
+                from: format!("account_{}", account_id),
+                to: library.clone(),
+                label: "input".to_string(),
+                dashed: false,
+            });
+        }
+
+        for account_id in link.output_accounts_id.iter() {
+            edges.push(Edge {
+                from: library.clone(),
+                to: format!("account_{}", account_id),
+                label: "output".to_string(),
+                dashed: false,
+            });
+        }
+    }
+
+    let mut authorizations = vec![];
+    for (i, authorization) in program_config.authorizations.iter().enumerate() {
+        let id = format!("authorization_{}", i);
+
+        for function in get_authorization_functions(authorization) {
+            if let LibraryAccountType::LibraryId(library_id) = function.contract_address {
+                edges.push(Edge {
+                    from: id.clone(),
+                    to: format!("library_{}", library_id),
+                    label: function.message_details.message.name.clone(),
+                    dashed: true,
+                });
+            }
+        }
+
+        authorizations.push(Node {
+            id,
+            label: format!(
+                "{}\n{}",
+                authorization.label,
+                authorization_mode(authorization)
+            ),
+            is_account: false,
+        });
+    }
+
+    Graph {
+        clusters,
+        authorizations,
+        edges,
+    }
+}
+
+fn to_mermaid(graph: &Graph) -> String {
+    let label = |label: &str| label.replace('"', "#quot;").replace('\n', "<br/>");
+    let mut diagram = String::from("flowchart LR\n");
+
+    for (domain, nodes) in graph.clusters.iter() {
+        diagram.push_str(&format!("    subgraph {}\n", domain));
+        for node in nodes {
+            let (open, close) = if node.is_account {
+                ("[(", ")]")
+            } else {
+                ("[", "]")
+            };
+            diagram.push_str(&format!(
+                "        {}{}\"{}\"{}\n",
+                node.id,
+                open,
+                label(&node.label),
+                close
+            ));
+        }
+        diagram.push_str("    end\n");
+    }
+
+    for node in graph.authorizations.iter() {
+        diagram.push_str(&format!(
+            "    {}{{{{\"{}\"}}}}\n",
+            node.id,
+            label(&node.label)
+        ));
+    }
+
+    for edge in graph.edges.iter() {
+        let arrow = if edge.dashed { "-.->" } else { "-->" };
+        diagram.push_str(&format!(
+            "    {} {}|\"{}\"| {}\n",
+            edge.from,
+            arrow,
+            label(&edge.label),
+            edge.to
+        ));
+    }
+
+    diagram
+}
+
+fn to_dot(graph: &Graph, name: &str) -> String {
+    let label = |label: &str| label.replace('"', "\\\"").replace('\n', "\\n");
+    let mut diagram = format!("digraph \"{}\" {{\n    rankdir=LR;\n", label(name));
+
+    for (domain, nodes) in graph.clusters.iter() {
+        diagram.push_str(&format!(
+            "    subgraph \"cluster_{}\" {{\n        label=\"{}\";\n",
+            domain, domain
+        ));
+        for node in nodes {
+            let shape = if node.is_account { "cylinder" } else { "box" };
+            diagram.push_str(&format!(
+                "        {} [label=\"{}\", shape={}];\n",
+                node.id,
+                label(&node.label),
+                shape
+            ));
+        }
+        diagram.push_str("    }\n");
+    }
+
+    for node in graph.authorizations.iter() {
+        diagram.push_str(&format!(
+            "    {} [label=\"{}\", shape=hexagon];\n",
+            node.id,
+            label(&node.label)
+        ));
+    }
+
+    for edge in graph.edges.iter() {
+        let style = if edge.dashed { ", style=dashed" } else { "" };
+        diagram.push_str(&format!(
+            "    {} -> {} [label=\"{}\"{}];\n",
+            edge.from,
+            edge.to,
+            label(&edge.label),
+            style
+        ));
+    }
+
+    diagram.push_str("}\n");
+
+    diagram
+}
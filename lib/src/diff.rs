@@ -0,0 +1,166 @@
+use serde_json::{json, Map, Value};
+use valence_library_utils::LibraryAccountType;
+use valence_program_manager::program_config::ProgramConfig;
+
+/// Name of the account or library an id points to, so renumbered ids are not reported as changes
+fn get_id_name(program_config: &ProgramConfig, account_type: &LibraryAccountType) -> String {
+    match account_type {
+        LibraryAccountType::Addr(addr) => addr.clone(),
+        LibraryAccountType::AccountId(id) => program_config
+            .accounts
+            .get(id)
+            .map(|account| format!("account {}", account.name))
+            .unwrap_or(format!("account {}", id)),
+        LibraryAccountType::LibraryId(id) => program_config
+            .libraries
+            .get(id)
+            .map(|library| format!("library {}", library.name))
+            .unwrap_or(format!("library {}", id)),
+    }
+}
+
+/// Replace the account and library ids of a value by their names
+fn replace_ids(program_config: &ProgramConfig, value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            if map.len() == 1 {
+                if let Ok(account_type) =
+                    serde_json::from_value::<LibraryAccountType>(Value::Object(map.clone()))
+                {
+                    return Value::String(get_id_name(program_config, &account_type));
+                }
+            }
+
+            Value::Object(
+                map.into_iter()
+                    .map(|(k, v)| (k, replace_ids(program_config, v)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|v| replace_ids(program_config, v))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// The program config with accounts, libraries, links and authorizations keyed by name or label
+fn get_labeled_config(program_config: &ProgramConfig) -> Value {
+    let to_value = |value: Value| replace_ids(program_config, value);
+
+    let accounts: Map<String, Value> = program_config
+        .accounts
+        .values()
+        .map(|account| {
+            (
+                account.name.clone(),
+                to_value(serde_json::to_value(account).unwrap_or_default()),
+            )
+        })
+        .collect();
+
+    let libraries: Map<String, Value> = program_config
+        .libraries
+        .values()
+        .map(|library| {
+            (
+                library.name.clone(),
+                to_value(serde_json::to_value(library).unwrap_or_default()),
+            )
+        })
+        .collect();
+
+    let account_names = |ids: &[u64]| {
+        ids.iter()
+            .map(|id| get_id_name(program_config, &LibraryAccountType::AccountId(*id)))
+            .collect::<Vec<_>>()
+    };
+    let links: Map<String, Value> = program_config
+        .links
+        .values()
+        .map(|link| {
+            (
+                get_id_name(
+                    program_config,
+                    &LibraryAccountType::LibraryId(link.library_id),
+                ),
+                json!({
+                    "input_accounts": account_names(&link.input_accounts_id),
+                    "output_accounts": account_names(&link.output_accounts_id),
+                }),
+            )
+        })
+        .collect();
+
+    let authorizations: Map<String, Value> = program_config
+        .authorizations
+        .iter()
+        .map(|authorization| {
+            (
+                authorization.label.clone(),
+                to_value(serde_json::to_value(authorization).unwrap_or_default()),
+            )
+        })
+        .collect();
+
+    json!({
+        "name": program_config.name,
+        "owner": program_config.owner,
+        "accounts": accounts,
+        "libraries": libraries,
+        "links": links,
+        "authorizations": authorizations,
+        "authorization_data": serde_json::to_value(&program_config.authorization_data).unwrap_or_default(),
+    })
+}
+
+fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<String>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old.iter() {
+                match new.get(key) {
+                    Some(new_value) => diff_values(&join(key), old_value, new_value, changes),
+                    None => changes.push(format!("- {}: {}", join(key), old_value)),
+                }
+            }
+
+            for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                changes.push(format!("+ {}: {}", join(key), new_value));
+            }
+        }
+        (Value::Array(old_values), Value::Array(new_values))
+            if old_values.len() == new_values.len() =>
+        {
+            for (i, (old_value, new_value)) in old_values.iter().zip(new_values).enumerate() {
+                diff_values(&join(&i.to_string()), old_value, new_value, changes);
+            }
+        }
+        (old, new) if old != new => changes.push(format!("~ {}: {} -> {}", path, old, new)),
+        _ => (),
+    }
+}
+
+/// Changes from the old to the new program config, accounts, libraries and authorizations are aligned by label
+pub fn diff_program_configs(old: &ProgramConfig, new: &ProgramConfig) -> Vec<String> {
+    let mut changes = vec![];
+
+    diff_values(
+        "",
+        &get_labeled_config(old),
+        &get_labeled_config(new),
+        &mut changes,
+    );
+
+    changes
+}
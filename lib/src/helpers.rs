@@ -1,4 +1,8 @@
-use std::{error::Error, path::PathBuf};
+use sha2::{Digest, Sha256};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
 
 // Verify the program path exists and everything was called from the right place
 pub(crate) fn verify_path(path: PathBuf) -> Result<(), Box<dyn Error>> {
@@ -20,3 +24,25 @@ pub(crate) fn verify_path(path: PathBuf) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Sha256 of a file, as hex
+pub(crate) fn hash_file(path: &Path) -> Result<String, Box<dyn Error>> {
+    Ok(hex::encode(Sha256::digest(std::fs::read(path)?)))
+}
+
+/// Sha256 of all files in a directory, with their relative paths so renames change the hash
+pub(crate) fn hash_dir(path: &Path) -> Result<String, Box<dyn Error>> {
+    let mut files = glob::glob(&format!("{}/**/*", path.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.is_file())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(file.strip_prefix(path)?.to_string_lossy().as_bytes());
+        hasher.update(std::fs::read(&file)?);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
@@ -0,0 +1,243 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use valence_authorization_utils::{
+    authorization::{AuthorizationInfo, AuthorizationModeInfo},
+    authorization_message::ParamRestriction,
+};
+use valence_library_utils::LibraryAccountType;
+use valence_program_manager::program_config::ProgramConfig;
+
+use crate::program_config::{
+    get_authorization_functions, get_function_chain_name, get_variant_name,
+};
+
+/// A function of an authorization, with the message template it accepts
+#[derive(Debug, Clone)]
+pub struct FunctionInvocation {
+    pub domain: String,
+    /// Address of the target contract, None if the program was not instantiated
+    pub contract_address: Option<String>,
+    /// Type of the library that executes the message, Example: `valence_forwarder_library`,
+    /// None if the target is not a library of the program
+    pub library: Option<String>,
+    pub message: Value,
+    /// Restrictions of the authorization on the message
+    pub restrictions: Vec<ParamRestriction>,
+}
+
+/// The messages to send to the authorization contract to trigger an authorization
+#[derive(Debug, Clone)]
+pub struct AuthorizationInvocation {
+    pub label: String,
+    pub permissionless: bool,
+    pub functions: Vec<FunctionInvocation>,
+}
+
+impl AuthorizationInvocation {
+    /// `send_msgs` ExecuteMsg of the authorization contract, with every message encoded in base64
+    pub fn send_msgs(&self) -> Value {
+        let messages = self
+            .functions
+            .iter()
+            .map(|function| {
+                json!({
+                    "cosmwasm_execute_msg": {
+                        "msg": STANDARD.encode(function.message.to_string()),
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "permissionless_action": {
+                "send_msgs": {
+                    "label": self.label,
+                    "messages": messages,
+                    "ttl": null,
+                }
+            }
+        })
+    }
+
+    /// Ready to fill template to trigger the authorization: fill the fields of the messages
+    /// left empty by the restrictions, then encode them in the `send_msgs` ExecuteMsg
+    pub fn template(&self, authorization_addr: &str) -> Value {
+        let functions = self
+            .functions
+            .iter()
+            .map(|function| {
+                json!({
+                    "domain": function.domain,
+                    "contract_address": function.contract_address,
+                    "message": function.message,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "label": self.label,
+            "permissionless": self.permissionless,
+            "authorization_contract": authorization_addr,
+            "functions": functions,
+            "execute_msg": self.send_msgs(),
+        })
+    }
+
+    /// File name of the template, Example: `provide_liquidity.json`
+    pub fn template_file_name(&self) -> String {
+        let name = self
+            .label
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        format!("{}.json", name)
+    }
+}
+
+/// Check that no two authorizations write their template to the same file,
+/// Example: `a/b` and `a_b` are both `a_b.json`
+pub(crate) fn check_template_file_names(
+    invocations: &[AuthorizationInvocation],
+) -> Result<(), String> {
+    let mut file_names: BTreeMap<String, &str> = BTreeMap::new();
+
+    for invocation in invocations {
+        // Case insensitive file systems would overwrite `A.json` with `a.json`
+        let file_name = invocation.template_file_name().to_lowercase();
+        if let Some(other) = file_names.insert(file_name, &invocation.label) {
+            return Err(format!(
+                "Labels {} and {} have the same invocation template file {}, rename one of them",
+                other,
+                invocation.label,
+                invocation.template_file_name()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Decode the value of a `MustBeValue` restriction, a base64 encoded JSON value
+pub(crate) fn decode_restriction_value(value: &impl serde::Serialize) -> Value {
+    let bytes = match serde_json::to_value(value) {
+        Ok(Value::String(encoded)) => STANDARD.decode(encoded).unwrap_or_default(),
+        Ok(value) => serde_json::from_value::<Vec<u8>>(value).unwrap_or_default(),
+        Err(_) => vec![],
+    };
+
+    serde_json::from_slice(&bytes).unwrap_or(Value::Null)
+}
+
+/// Insert a value at a path of a JSON object, creating the objects on the way
+fn insert_path(message: &mut Value, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+
+    let mut current = message;
+    for key in parents {
+        if !current.is_object() {
+            *current = Value::Object(Map::new());
+        }
+        current = current
+            .as_object_mut()
+            .expect("Value should be an object")
+            .entry(key.clone())
+            .or_insert(Value::Object(Map::new()));
+    }
+
+    if !current.is_object() {
+        *current = Value::Object(Map::new());
+    }
+    let object = current.as_object_mut().expect("Value should be an object");
+    // A value required by another restriction is kept
+    if !object.contains_key(last) || !value.is_object() {
+        object.insert(last.clone(), value);
+    }
+}
+
+/// Smallest message that satisfies the restrictions, fields not set by them are left to fill,
+/// Example: `MustBeIncluded(["process_function", "forward"])` -> `{"process_function": {"forward": {}}}`
+pub fn message_template(name: &str, restrictions: Option<&Vec<ParamRestriction>>) -> Value {
+    let mut message = json!({ name: {} });
+
+    for restriction in restrictions.into_iter().flatten() {
+        match restriction {
+            ParamRestriction::MustBeIncluded(path) => {
+                insert_path(&mut message, path, Value::Object(Map::new()))
+            }
+            ParamRestriction::MustBeValue(path, value) => {
+                insert_path(&mut message, path, decode_restriction_value(value))
+            }
+            ParamRestriction::CannotBeIncluded(_) => (),
+        }
+    }
+
+    message
+}
+
+fn get_contract_address(
+    program_config: &ProgramConfig,
+    contract_address: &LibraryAccountType,
+) -> Option<String> {
+    match contract_address {
+        LibraryAccountType::Addr(addr) => Some(addr.clone()),
+        LibraryAccountType::AccountId(id) => program_config.accounts.get(id)?.addr.clone(),
+        LibraryAccountType::LibraryId(id) => program_config.libraries.get(id)?.addr.clone(),
+    }
+}
+
+pub fn get_authorization_invocation(
+    program_config: &ProgramConfig,
+    authorization: &AuthorizationInfo,
+) -> AuthorizationInvocation {
+    AuthorizationInvocation {
+        label: authorization.label.clone(),
+        permissionless: matches!(authorization.mode, AuthorizationModeInfo::Permissionless),
+        functions: get_authorization_functions(authorization)
+            .into_iter()
+            .map(|function| FunctionInvocation {
+                domain: get_function_chain_name(function.domain).to_string(),
+                contract_address: get_contract_address(program_config, function.contract_address),
+                library: match function.contract_address {
+                    LibraryAccountType::LibraryId(id) => program_config
+                        .libraries
+                        .get(id)
+                        .and_then(|library| get_variant_name(&library.config)),
+                    _ => None,
+                },
+                message: message_template(
+                    &function.message_details.message.name,
+                    function
+                        .message_details
+                        .message
+                        .params_restrictions
+                        .as_ref(),
+                ),
+                restrictions: function
+                    .message_details
+                    .message
+                    .params_restrictions
+                    .clone()
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    }
+}
+
+/// Invocations of every authorization of the program
+pub fn get_invocations(program_config: &ProgramConfig) -> Vec<AuthorizationInvocation> {
+    program_config
+        .authorizations
+        .iter()
+        .map(|authorization| get_authorization_invocation(program_config, authorization))
+        .collect()
+}
@@ -1,21 +1,88 @@
+mod address;
+mod address_book;
+mod assets;
+mod authorize;
+mod codegen;
+mod dependencies;
+mod diagram;
+mod diff;
 mod helpers;
+mod invocation;
+mod lints;
 mod manager_config;
+mod manifest;
+mod messages;
+pub mod outputs;
+mod params_schema;
+mod preflight;
 mod program_config;
 mod program_params;
+mod references;
+mod report;
+mod restrictions;
+mod validation;
 
-use std::{ error::Error, io::Write, path::PathBuf};
+use std::{
+    error::Error,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
+use address::check_program_addresses;
+use assets::{get_asset_registry, resolve_asset_params};
 use chrono::Utc;
-use clap::{command, Parser};
+use clap::{command, Parser, ValueEnum};
+use dependencies::{deploy_dependencies, get_deployment_order};
+use diagram::program_diagram;
 use dotenvy::dotenv;
 use helpers::verify_path;
-use manager_config::set_manager_config;
+use invocation::check_template_file_names;
+use manager_config::{get_manager_config, set_manager_config};
+use manifest::MANIFEST_FILE;
+use params_schema::{get_params_schema, ParamsReport};
 use program_config::read_program_config_from_json;
-use program_params::get_program_params;
-use valence_program_manager::program_config::ProgramConfig;
+use program_params::{get_program_params, parse_param_override};
+use references::resolve_reference_params;
+use report::program_report;
+use valence_program_manager::{config::Config, program_config::ProgramConfig};
 
 // Reexport params to programs
 pub use program_params::ProgramParams;
+// Reexport address validation to programs
+pub use address::{validate_address, validate_domain_address, AddressKind};
+// Reexport the asset registry to programs
+pub use assets::{ibc_denom, parse_amount, Asset, AssetRegistry};
+// Reexport the references to other deployments to programs
+pub use references::ProgramReference;
+// Reexport the dependency graph
+pub use dependencies::{get_program_dependencies, ProgramNode};
+// Reexport the program config checks
+pub use restrictions::{check_restrictions_against_libraries, evaluate_authorization};
+pub use validation::validate_program_config;
+// Reexport the address book export
+pub use address_book::{get_address_book, program_address_book, AddressBookFormat, AddressEntry};
+// Reexport the client code generation
+pub use codegen::{rust_client, typescript_client, CodegenLanguage};
+// Reexport the config diff
+pub use diff::diff_program_configs;
+// Reexport the diagram export
+pub use diagram::DiagramFormat;
+pub use lints::{get_lints_config, lint_program_config, Lint, LintRule, Severity, LINT_RULES};
+pub use preflight::{get_required_code_ids, preflight_check};
+// Reexport the canonical serialization of program configs
+pub use program_config::{canonical_program_config, program_fingerprint};
+// Reexport the authorization invocations
+pub use invocation::{
+    get_authorization_invocation, get_invocations, message_template, AuthorizationInvocation,
+    FunctionInvocation,
+};
+// Reexport the typed library function messages
+pub use authorize::{add_library, authorize, Authorize, PermissionedAuthorize, ProgramLibrary};
+pub use messages::{process_function_message, process_function_restriction, LibraryFunction};
+// Reexport the deployment manifest
+pub use manifest::{CrateInfo, DeploymentManifest, DeploymentOutcome, GitInfo};
+// Reexport the deployment report
+pub use report::ReportFormat;
 
 // |X| - Read or get the manager config
 // |X| - read program parameters into a map
@@ -33,6 +100,82 @@ struct Args {
     /// Absolute path to the program config json file
     #[arg(short, long)]
     program_config_path: Option<String>,
+    /// Print the effective program params for the target environment and exit
+    #[arg(long)]
+    show_params: bool,
+    /// Override a program param, can be repeated Ex: --param owner=neutron1...
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param_override)]
+    params: Vec<(String, String)>,
+    /// Report every missing, mistyped and unused param for the target environment and exit
+    #[arg(long)]
+    check_params: bool,
+    /// Print a markdown reference table of the params declared in program_params/schema.toml and exit
+    #[arg(long)]
+    params_doc: bool,
+    /// Print the deployment order of the program and the programs it depends on and exit
+    #[arg(long)]
+    show_dependencies: bool,
+    /// Deploy the programs this program depends on, in order, before deploying it
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "diagram",
+            "report",
+            "address_book",
+            "invocation_templates",
+            "diff_with",
+            "evaluate",
+        ]
+    )]
+    with_dependencies: bool,
+    /// Build the program and run all checks without deploying it
+    #[arg(long)]
+    dry_run: bool,
+    /// Print a diagram of the program and exit, use with --program-config-path to draw a config from output/
+    #[arg(long, value_enum)]
+    diagram: Option<DiagramFormat>,
+    /// Print a report of the program and exit, use with --program-config-path to report a config from output/
+    #[arg(long, value_enum)]
+    report: Option<ReportFormat>,
+    /// Print the addresses of the program by domain and exit, use with --program-config-path to export a deployment from output/
+    #[arg(long, value_enum)]
+    address_book: Option<AddressBookFormat>,
+    /// Print the invocation template of every authorization of the program, keyed by label, and exit,
+    /// use with --program-config-path to generate them from a deployment in output/
+    #[arg(long)]
+    invocation_templates: bool,
+    /// Print the changes from a program config file to this program and exit,
+    /// `latest` compares a fresh build with the latest raw config of the target env in output/
+    #[arg(long, value_name = "PATH|latest")]
+    diff_with: Option<String>,
+    /// Check if an authorization would accept --message, without sending it, and exit
+    #[arg(long, value_name = "LABEL", requires = "message")]
+    evaluate: Option<String>,
+    /// Message to evaluate, a JSON message or an array of messages Ex: '{"process_function":{"forward":{}}}'
+    #[arg(long, requires = "evaluate")]
+    message: Option<String>,
+    /// Sender of the evaluated message, required by permissioned authorizations
+    #[arg(long, requires = "evaluate")]
+    sender: Option<String>,
+    /// Deploy even if an identical program config was already deployed on the target env
+    #[arg(long)]
+    force: bool,
+    /// Exit successfully without deploying if an identical program config was already deployed on the target env
+    #[arg(long, conflicts_with = "force")]
+    skip_existing: bool,
+    /// Print a client module with the addresses and authorizations of the latest deployment on the target env and exit,
+    /// use with --program-config-path to generate it from a deployment in output/
+    #[arg(long, value_enum)]
+    codegen: Option<CodegenLanguage>,
+    /// Generate the client from the latest deployment of every env of the program
+    #[arg(long, requires = "codegen", conflicts_with = "program_config_path")]
+    all_envs: bool,
+    /// Print the deployments of the program, from every env, and exit
+    #[arg(long)]
+    list_deployments: bool,
+    /// Print the output directory of the latest instantiated deployment of the program on the target env and exit
+    #[arg(long)]
+    latest: bool,
 }
 
 pub async fn main<F>(program_path: &str, builder: F) -> Result<(), Box<dyn Error>>
@@ -57,43 +200,533 @@ where
 
     verify_path(program_path.clone())?;
 
+    let program_name = program_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .expect("Program path should have a name")
+        .to_string();
+
+    if args.list_deployments {
+        for entry in outputs::list(&program_name)? {
+            println!(
+                "{}  {}  {:?}  fingerprint: {}  program id: {}",
+                entry.timestamp,
+                entry.env.as_deref().unwrap_or("-"),
+                entry.status,
+                entry.fingerprint.as_deref().unwrap_or("-"),
+                entry
+                    .program_id
+                    .map(|id| id.to_string())
+                    .unwrap_or("-".to_string())
+            );
+        }
+        return Ok(());
+    }
+
+    if args.latest {
+        let entry = outputs::latest(&program_name, &args.target_env)?.ok_or(format!(
+            "No instantiated deployment of {} found for env {}",
+            program_name, args.target_env
+        ))?;
+        println!("{}", entry.path.display());
+        return Ok(());
+    }
+
+    if let Some(language) = args.codegen {
+        let deployments = get_codegen_deployments(&program_name, &args)?;
+
+        match language {
+            CodegenLanguage::Rust => {
+                for (env, program_config) in deployments.iter() {
+                    print!("{}", rust_client(&program_name, env, program_config)?);
+                }
+            }
+            CodegenLanguage::Typescript => {
+                print!("{}", typescript_client(&program_name, &deployments)?)
+            }
+        }
+        return Ok(());
+    }
+
+    if args.show_dependencies {
+        for (program, env) in get_deployment_order(&program_name, &args.target_env)? {
+            println!("{}@{}", program, env);
+        }
+        return Ok(());
+    }
+
+    // Read the manager config for the chosen environment
+    let manager_config = get_manager_config(&args.target_env)?;
+
+    if args.params_doc {
+        let schema = get_params_schema(&program_path)?
+            .ok_or("Program params schema not found in program_params/schema.toml")?;
+        print!("{}", schema.to_markdown());
+        return Ok(());
+    }
+
+    // Only show or check the effective params without deploying
+    if args.show_params || args.check_params {
+        let (program_params, report) = load_program_params(&program_path, &args, &manager_config)?;
+
+        if args.show_params {
+            print!("{}", program_params);
+        }
+
+        if args.check_params {
+            // Without a schema we can only track which params the builder reads
+            let report = report.unwrap_or_else(|| {
+                let params_tracker = program_params.clone();
+                builder(program_params);
+                ParamsReport {
+                    unused: params_tracker.unused_keys(),
+                    ..Default::default()
+                }
+            });
+
+            print!("{}", report);
+
+            if report.has_errors() {
+                return Err("Program params are invalid".into());
+            }
+        }
+
+        return Ok(());
+    }
+
+    // Dependencies are deployed first so their addresses can be resolved in our params
+    if args.with_dependencies {
+        if args.dry_run {
+            // A dry run never instantiates contracts, not even for the dependencies
+            println!("Dry run, the dependencies are not deployed, deployment order:");
+            for (program, env) in get_deployment_order(&program_name, &args.target_env)? {
+                println!("{}@{}", program, env);
+            }
+        } else {
+            deploy_dependencies(&program_name, &args.target_env)?;
+        }
+    }
+
     // Set manager config for the chosen environment
-    set_manager_config(&args.target_env).await?;
-    
+    set_manager_config(manager_config.clone()).await?;
+
     // If a path to program_config.json was passed, use it
-    let mut program_config = if let Some(program_config_path) = args.program_config_path {
-        read_program_config_from_json(&program_config_path)
+    let (mut program_config, program_params) = if let Some(program_config_path) =
+        &args.program_config_path
+    {
+        (read_program_config_from_json(program_config_path), None)
     } else {
         // Else build the program config from the builder
-        let program_params = get_program_params(&program_path, &args.target_env)?;
+        let (program_params, report) = load_program_params(&program_path, &args, &manager_config)?;
+
+        if let Some(report) = report.filter(|r| r.has_errors()) {
+            return Err(format!("Program params are invalid:\n{}", report).into());
+        }
 
-        builder(program_params)
+        let params_tracker = program_params.clone();
+        let program_config = builder(program_params);
+
+        let unused = params_tracker.unused_keys();
+        if !unused.is_empty() {
+            eprintln!(
+                "Warning: params not used by the builder: {}",
+                unused.join(", ")
+            );
+        }
+
+        (program_config, Some(params_tracker))
     };
 
+    if let Some(format) = args.diagram {
+        print!("{}", program_diagram(&program_config, format));
+        return Ok(());
+    }
+
+    if let Some(format) = args.report {
+        print!(
+            "{}",
+            program_report(
+                &program_config,
+                program_params.as_ref(),
+                &args.target_env,
+                &manager_config,
+                format
+            )
+        );
+        return Ok(());
+    }
+
+    if let Some(format) = args.address_book {
+        print!("{}", program_address_book(&program_config, format)?);
+        return Ok(());
+    }
+
+    if args.invocation_templates {
+        let templates = get_invocations(&program_config)
+            .iter()
+            .map(|invocation| {
+                (
+                    invocation.label.clone(),
+                    invocation.template(&program_config.authorization_data.authorization_addr),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        println!("{}", serde_json::to_string_pretty(&templates)?);
+        return Ok(());
+    }
+
+    if let Some(diff_with) = &args.diff_with {
+        let old_path = match diff_with.as_str() {
+            "latest" => outputs::list(&program_name)?
+                .into_iter()
+                .rfind(|entry| {
+                    entry.env.as_deref() == Some(args.target_env.as_str())
+                        && entry.raw_config_path().exists()
+                })
+                .map(|entry| entry.raw_config_path())
+                .ok_or(format!(
+                    "No deployment of {} found for env {}",
+                    program_name, args.target_env
+                ))?,
+            path => PathBuf::from(path),
+        };
+        let old_config = read_program_config_from_json(
+            old_path
+                .to_str()
+                .expect("Program config path should be a string"),
+        );
+
+        let changes = diff_program_configs(&old_config, &program_config);
+        if changes.is_empty() {
+            println!("No changes from {}", old_path.display());
+        }
+        for change in changes {
+            println!("{}", change);
+        }
+        return Ok(());
+    }
+
+    if let (Some(label), Some(message)) = (&args.evaluate, &args.message) {
+        let messages = serde_json::from_str(message)
+            .map_err(|e| format!("Failed to parse message : {}", e))?;
+
+        return match evaluate_authorization(
+            &program_config,
+            label,
+            &messages,
+            args.sender.as_deref(),
+        ) {
+            Ok(()) => {
+                println!("Authorization {} accepts the message", label);
+                Ok(())
+            }
+            Err(reasons) => Err(format!(
+                "Authorization {} rejects the message:\n{}",
+                label,
+                reasons.join("\n")
+            )
+            .into()),
+        };
+    }
+
+    // A typo in the owner or a permissioned address can't be fixed after the deployment
+    let address_errors = check_program_addresses(&program_config, &manager_config);
+    if !address_errors.is_empty() {
+        return Err(format!(
+            "Invalid addresses in program config:\n{}",
+            address_errors.join("\n")
+        )
+        .into());
+    }
+
+    // A misspelled library reference should not be found only on-chain
+    let validation_errors = validate_program_config(&program_config);
+    if !validation_errors.is_empty() {
+        return Err(format!("Invalid program config:\n{}", validation_errors.join("\n")).into());
+    }
+
+    // The templates are written after the deployment, when it is too late to rename a label
+    check_template_file_names(&get_invocations(&program_config))?;
+
+    // Lints with error severity fail the deployment, warnings are only printed
+    let lints = lint_program_config(&program_config, &get_lints_config(&program_path)?);
+    for lint in lints.iter() {
+        eprintln!("{}", lint);
+    }
+    if lints.iter().any(|lint| lint.severity == Severity::Error) {
+        return Err("Program config has lint errors".into());
+    }
+
+    // Every contract must have a code id on its domain in the manager config of the env
+    let preflight_errors = preflight_check(&program_config, &manager_config);
+    if !preflight_errors.is_empty() {
+        return Err(format!(
+            "Preflight check failed for {} environment:\n{}",
+            args.target_env,
+            preflight_errors.join("\n")
+        )
+        .into());
+    }
+
+    let fingerprint = program_fingerprint(&program_config);
+
+    if args.dry_run {
+        println!(
+            "Program {} is valid, nothing was deployed\nFingerprint: {}",
+            program_config.name, fingerprint
+        );
+        return Ok(());
+    }
+
+    // Running the deployer twice by accident would instantiate a second set of contracts
+    if !args.force {
+        // The latest identical deployment that was instantiated
+        if let Some(deployment) = outputs::list(&program_name)?.into_iter().rfind(|entry| {
+            entry.env.as_deref() == Some(args.target_env.as_str())
+                && entry.fingerprint.as_deref() == Some(fingerprint.as_str())
+                && entry.is_instantiated()
+        }) {
+            let instantiated_path = deployment.instantiated_config_path();
+
+            if args.skip_existing {
+                println!(
+                    "Program {} is already deployed on {}: {}",
+                    program_config.name,
+                    args.target_env,
+                    instantiated_path.display()
+                );
+                return Ok(());
+            }
+
+            return Err(format!(
+                "An identical program config is already deployed on {}: {}\nUse --force to deploy it again",
+                args.target_env,
+                instantiated_path.display()
+            )
+            .into());
+        }
+    }
+
+    // Record the target env and effective params so the deployment can be reproduced
+    write_params_to_output(
+        program_params.as_ref(),
+        &args.target_env,
+        &program_path,
+        &timestamp,
+    )?;
+
     // Write the raw program config to file
     write_to_output(program_config.clone(), &program_path, &timestamp, "raw")?;
 
+    // Same format as sha256sum, so the raw config can be checked against a reviewed plan
+    std::fs::write(
+        get_output_path(&program_path, &timestamp)?.join("raw-program-config.sha256"),
+        format!("{}  raw-program-config.json\n", fingerprint),
+    )?;
+
+    // Record how the deployment was produced, and update it with the outcome
+    let output_path = get_output_path(&program_path, &timestamp)?;
+    let mut manifest = DeploymentManifest::new(
+        &program_path,
+        &args.target_env,
+        &program_config,
+        program_params.as_ref(),
+        &manager_config,
+    )?;
+    manifest.write(&output_path)?;
+
     // Use program manager to deploy the program
-    valence_program_manager::init_program(&mut program_config).await?;
+    let deploy_result = valence_program_manager::init_program(&mut program_config).await;
 
-    // Write instantiated program to file
-    write_to_output(program_config, &program_path, &timestamp, "instantiated")?;
+    manifest.finish(deploy_result.as_ref().err().map(|e| e.to_string()));
+    if let Err(e) = deploy_result {
+        warn_on_error(MANIFEST_FILE, manifest.write(&output_path));
+        warn_on_error("deployments/index.json", outputs::write_index());
+        return Err(e.into());
+    }
+
+    // Write instantiated program to file first, it is the only record of the deployed addresses
+    write_to_output(
+        program_config.clone(),
+        &program_path,
+        &timestamp,
+        "instantiated",
+    )?;
+
+    // Files derived from the instantiated program only warn on failure, the program is already deployed
+    warn_on_error(MANIFEST_FILE, manifest.write(&output_path));
+
+    // Write the diagram of the instantiated program, with the contract addresses
+    warn_on_error(
+        "program-diagram.mmd",
+        std::fs::write(
+            output_path.join("program-diagram.mmd"),
+            program_diagram(&program_config, DiagramFormat::Mermaid),
+        )
+        .map_err(Into::into),
+    );
+
+    // Write the report of the deployment for operators and auditors
+    let report_format = ReportFormat::Markdown;
+    warn_on_error(
+        report_format.file_name(),
+        std::fs::write(
+            output_path.join(report_format.file_name()),
+            program_report(
+                &program_config,
+                program_params.as_ref(),
+                &args.target_env,
+                &manager_config,
+                report_format,
+            ),
+        )
+        .map_err(Into::into),
+    );
+
+    // Write the addresses for downstream services in every format
+    for format in AddressBookFormat::value_variants() {
+        warn_on_error(
+            format.file_name(),
+            program_address_book(&program_config, *format).and_then(|content| {
+                Ok(std::fs::write(
+                    output_path.join(format.file_name()),
+                    content,
+                )?)
+            }),
+        );
+    }
+
+    // Write what operators need to send to the authorization contract to trigger each authorization
+    warn_on_error(
+        "authorizations/",
+        write_invocation_templates(&program_config, &output_path.join("authorizations")),
+    );
+
+    // The index is rebuilt once the instantiated config exists, so the entry has its program id
+    warn_on_error("deployments/index.json", outputs::write_index());
 
     Ok(())
 }
 
-fn write_to_output(
-    program_config: ProgramConfig,
-    program_path: &PathBuf,
-    time: &str,
-    prefix: &str,
+/// Print a warning if a file derived from the deployed program couldn't be written
+fn warn_on_error(file: &str, result: Result<(), Box<dyn Error>>) {
+    if let Err(e) = result {
+        eprintln!("Warning: failed to write {} : {}", file, e);
+    }
+}
+
+/// Write the invocation template of every authorization, named after its label
+fn write_invocation_templates(
+    program_config: &ProgramConfig,
+    templates_path: &Path,
 ) -> Result<(), Box<dyn Error>> {
+    let invocations = get_invocations(program_config);
+    check_template_file_names(&invocations)?;
+
+    std::fs::create_dir_all(templates_path)?;
+
+    for invocation in invocations {
+        std::fs::write(
+            templates_path.join(invocation.template_file_name()),
+            serde_json::to_string_pretty(
+                &invocation.template(&program_config.authorization_data.authorization_addr),
+            )?,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Program configs to generate a client from, keyed by env: the given config file,
+/// the latest deployment of the target env or the latest deployment of every env
+fn get_codegen_deployments(
+    program_name: &str,
+    args: &Args,
+) -> Result<Vec<(String, ProgramConfig)>, Box<dyn Error>> {
+    if let Some(path) = &args.program_config_path {
+        return Ok(vec![(
+            args.target_env.clone(),
+            read_program_config_from_json(path),
+        )]);
+    }
+
+    let mut envs = outputs::list(program_name)?
+        .into_iter()
+        .filter_map(|entry| entry.env)
+        .collect::<Vec<_>>();
+    envs.sort();
+    envs.dedup();
+
+    if !args.all_envs {
+        envs = vec![args.target_env.clone()];
+    }
+
+    let mut deployments = vec![];
+    for env in envs {
+        let entry = outputs::latest(program_name, &env)?.ok_or(format!(
+            "No instantiated deployment of {} found for env {}",
+            program_name, env
+        ))?;
+
+        deployments.push((
+            env,
+            read_program_config_from_json(
+                entry
+                    .instantiated_config_path()
+                    .to_str()
+                    .expect("Output path should be a string"),
+            ),
+        ));
+    }
+
+    Ok(deployments)
+}
+
+/// Read the params for the target env, resolve the references and assets, fill the schema defaults and check them if the program has a schema
+fn load_program_params(
+    program_path: &Path,
+    args: &Args,
+    manager_config: &Config,
+) -> Result<(ProgramParams, Option<ParamsReport>), Box<dyn Error>> {
+    let mut program_params = get_program_params(program_path, &args.target_env, &args.params)?;
+
+    // Resolve references to other deployments and assets to denoms before checking the params
+    resolve_reference_params(&mut program_params)?;
+
+    // The schema declares the domain of the denoms
+    let schema = get_params_schema(program_path)?;
+
+    let asset_registry = get_asset_registry(&args.target_env)?;
+    resolve_asset_params(&mut program_params, &asset_registry, schema.as_ref())?;
+    program_params.set_asset_registry(asset_registry);
+
+    let report = schema.map(|schema| {
+        schema.apply_defaults(&mut program_params);
+        schema.check(&program_params, manager_config)
+    });
+
+    Ok((program_params, report))
+}
+
+fn get_output_path(program_path: &Path, time: &str) -> Result<PathBuf, Box<dyn Error>> {
     let path = program_path.join("output").join(time);
 
     if !path.exists() {
         std::fs::create_dir_all(path.clone())?;
     }
 
+    Ok(path)
+}
+
+fn write_to_output(
+    program_config: ProgramConfig,
+    program_path: &PathBuf,
+    time: &str,
+    prefix: &str,
+) -> Result<(), Box<dyn Error>> {
+    let path = get_output_path(program_path, time)?;
+
     // Construct the full file path
     let file_name = format!("{}-program-config.json", prefix);
     let file_path = path.join(file_name.clone());
@@ -102,9 +735,29 @@ fn write_to_output(
     let mut file = std::fs::File::create(file_path.clone())?;
 
     // Serialize the data to a string
-    let content = serde_json::to_string(&program_config)?;
+    let content = canonical_program_config(&program_config);
 
     file.write_all(content.as_bytes())?;
 
     Ok(())
 }
+
+fn write_params_to_output(
+    program_params: Option<&ProgramParams>,
+    target_env: &str,
+    program_path: &Path,
+    time: &str,
+) -> Result<(), Box<dyn Error>> {
+    let file_path = get_output_path(program_path, time)?.join("program-params.json");
+
+    // Params are not known when the program config was read from a file
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "target_env": target_env,
+        "params": program_params.map(|p| p.to_sorted_map()),
+        "references": program_params.map(|p| p.references()),
+    }))?;
+
+    std::fs::write(file_path, content)?;
+
+    Ok(())
+}
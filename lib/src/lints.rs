@@ -0,0 +1,294 @@
+use config::Config as ConfigHelper;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{collections::BTreeMap, error::Error, fmt, path::Path};
+use valence_authorization_utils::authorization::{
+    AuthorizationInfo, AuthorizationModeInfo, PermissionTypeInfo,
+};
+use valence_library_utils::LibraryAccountType;
+use valence_program_manager::{account::AccountType, program_config::ProgramConfig};
+
+use crate::{
+    program_config::get_authorization_functions, restrictions::check_restrictions_against_libraries,
+};
+
+/// File in the program directory where the severity of the lints can be changed
+const LINTS_FILE: &str = "lints.toml";
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Allow,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Allow => write!(f, "allow"),
+            Severity::Warn => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A lint rule over the program config, returns a message for every finding
+pub struct LintRule {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub default_severity: Severity,
+    check: fn(&ProgramConfig) -> Vec<String>,
+}
+
+pub const LINT_RULES: &[LintRule] = &[
+    LintRule {
+        id: "permissionless_fund_transfer",
+        description: "Permissionless authorization on a library that sends funds to an address outside of the program",
+        default_severity: Severity::Error,
+        check: check_permissionless_fund_transfer,
+    },
+    LintRule {
+        id: "missing_restrictions",
+        description: "Function without any param restriction, any message of the library can be executed",
+        default_severity: Severity::Warn,
+        check: check_missing_restrictions,
+    },
+    LintRule {
+        id: "unlimited_wallet_permission",
+        description: "Permission without call limit granted to a wallet",
+        default_severity: Severity::Warn,
+        check: check_unlimited_wallet_permission,
+    },
+    LintRule {
+        id: "owner_is_permissioned",
+        description: "Owner of the program is also a permissioned address of an authorization",
+        default_severity: Severity::Warn,
+        check: check_owner_is_permissioned,
+    },
+    LintRule {
+        id: "unknown_library_function",
+        description: "Param restriction that does not match any function of the library, the authorization would reject every message",
+        default_severity: Severity::Error,
+        check: check_restrictions_against_libraries,
+    },
+];
+
+#[derive(Debug, Clone)]
+pub struct Lint {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.rule, self.message)
+    }
+}
+
+/// Severity overrides keyed by rule id, Example: `missing_restrictions = "error"`
+pub fn get_lints_config(program_path: &Path) -> Result<BTreeMap<String, Severity>, Box<dyn Error>> {
+    let lints_path = std::env::current_dir()?.join(program_path).join(LINTS_FILE);
+
+    if !lints_path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let lints_config = ConfigHelper::builder()
+        .add_source(config::File::from(lints_path))
+        .build()?
+        .try_deserialize::<BTreeMap<String, Severity>>()
+        .map_err(|e| format!("Failed to parse lints config : {}", e))?;
+
+    if let Some(unknown) = lints_config
+        .keys()
+        .find(|id| !LINT_RULES.iter().any(|rule| rule.id == id.as_str()))
+    {
+        return Err(format!("Unknown lint rule {} in {}", unknown, LINTS_FILE).into());
+    }
+
+    Ok(lints_config)
+}
+
+/// Run every lint rule that is not allowed over the program config
+pub fn lint_program_config(
+    program_config: &ProgramConfig,
+    lints_config: &BTreeMap<String, Severity>,
+) -> Vec<Lint> {
+    let mut lints = vec![];
+
+    for rule in LINT_RULES {
+        let severity = lints_config
+            .get(rule.id)
+            .copied()
+            .unwrap_or(rule.default_severity);
+
+        if severity == Severity::Allow {
+            continue;
+        }
+
+        lints.extend(
+            (rule.check)(program_config)
+                .into_iter()
+                .map(|message| Lint {
+                    rule: rule.id,
+                    severity,
+                    message,
+                }),
+        );
+    }
+
+    lints
+}
+
+fn get_permissioned_addresses(authorization: &AuthorizationInfo) -> Vec<&str> {
+    match &authorization.mode {
+        AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithCallLimit(addrs)) => {
+            addrs.iter().map(|(addr, _)| addr.as_str()).collect()
+        }
+        AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithoutCallLimit(addrs)) => {
+            addrs.iter().map(|addr| addr.as_str()).collect()
+        }
+        AuthorizationModeInfo::Permissionless => vec![],
+    }
+}
+
+/// Addresses that belong to the program: existing and instantiated accounts and libraries
+fn get_program_addresses(program_config: &ProgramConfig) -> Vec<&str> {
+    let accounts = program_config.accounts.values().flat_map(|account| {
+        let existing = match &account.ty {
+            AccountType::Addr { addr } => Some(addr.as_str()),
+            _ => None,
+        };
+        existing.into_iter().chain(account.addr.as_deref())
+    });
+    let libraries = program_config
+        .libraries
+        .values()
+        .filter_map(|library| library.addr.as_deref());
+
+    accounts.chain(libraries).collect()
+}
+
+/// Fields of a library config the funds are sent to, Example: `output_addr`, `remote_chain_receiver`, the `account` of a split
+fn is_recipient_field(key: &str) -> bool {
+    key.starts_with("output")
+        || key == "account"
+        || key.contains("receiver")
+        || key.contains("recipient")
+}
+
+/// Literal addresses in the recipient fields of a library config, other addresses (pools, inputs) are skipped
+fn find_literal_addresses<'a>(value: &'a Value, in_recipient: bool, addrs: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) if in_recipient && bech32::decode(s).is_ok() => addrs.push(s),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|v| find_literal_addresses(v, in_recipient, addrs)),
+        Value::Object(map) => map.iter().for_each(|(k, v)| {
+            find_literal_addresses(v, in_recipient || is_recipient_field(k), addrs)
+        }),
+        _ => (),
+    }
+}
+
+fn check_permissionless_fund_transfer(program_config: &ProgramConfig) -> Vec<String> {
+    let program_addresses = get_program_addresses(program_config);
+    let mut findings = vec![];
+
+    for authorization in program_config.authorizations.iter() {
+        if !matches!(authorization.mode, AuthorizationModeInfo::Permissionless) {
+            continue;
+        }
+
+        for function in get_authorization_functions(authorization) {
+            let LibraryAccountType::LibraryId(id) = function.contract_address else {
+                continue;
+            };
+            let Some(library) = program_config.libraries.get(id) else {
+                continue;
+            };
+            let Ok(library_config) = serde_json::to_value(&library.config) else {
+                continue;
+            };
+
+            let mut addrs = vec![];
+            find_literal_addresses(&library_config, false, &mut addrs);
+
+            for addr in addrs {
+                if !program_addresses.contains(&addr) {
+                    findings.push(format!(
+                        "authorization {}: anyone can trigger library {} which sends funds to {}, an address outside of the program",
+                        authorization.label, library.name, addr
+                    ));
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+fn check_missing_restrictions(program_config: &ProgramConfig) -> Vec<String> {
+    let mut findings = vec![];
+
+    for authorization in program_config.authorizations.iter() {
+        for function in get_authorization_functions(authorization) {
+            let message = &function.message_details.message;
+
+            if message
+                .params_restrictions
+                .as_ref()
+                .is_none_or(|restrictions| restrictions.is_empty())
+            {
+                findings.push(format!(
+                    "authorization {}: function {} has no param restrictions",
+                    authorization.label, message.name
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+fn check_unlimited_wallet_permission(program_config: &ProgramConfig) -> Vec<String> {
+    let mut findings = vec![];
+
+    for authorization in program_config.authorizations.iter() {
+        let AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithoutCallLimit(addrs)) =
+            &authorization.mode
+        else {
+            continue;
+        };
+
+        // Wallet addresses are 20 bytes, contracts are 32 bytes
+        for addr in addrs {
+            if bech32::decode(addr).is_ok_and(|(_, data)| data.len() == 20) {
+                findings.push(format!(
+                    "authorization {}: wallet {} can call it without limit",
+                    authorization.label, addr
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+fn check_owner_is_permissioned(program_config: &ProgramConfig) -> Vec<String> {
+    program_config
+        .authorizations
+        .iter()
+        .filter(|authorization| {
+            get_permissioned_addresses(authorization).contains(&program_config.owner.as_str())
+        })
+        .map(|authorization| {
+            format!(
+                "authorization {}: owner {} is a permissioned address",
+                authorization.label, program_config.owner
+            )
+        })
+        .collect()
+}
@@ -1,7 +1,19 @@
 use config::Config as ConfigHelper;
 use std::{error::Error, path::PathBuf};
 
-const MANAGER_CONFIGS_REPO_URL: &str = "https://github.com/timewave-computer/valence-program-manager-config.git";
+use crate::helpers::hash_dir;
+
+const MANAGER_CONFIGS_REPO_URL: &str =
+    "https://github.com/timewave-computer/valence-program-manager-config.git";
+
+/// Sha256 of all files of the manager config of the env
+pub(crate) fn get_manager_config_hash(env: &str) -> Result<String, Box<dyn Error>> {
+    hash_dir(
+        &std::env::current_dir()?
+            .join("manager_configs")
+            .join(env.to_lowercase()),
+    )
+}
 
 pub fn get_manager_config(
     path: &str,
@@ -64,10 +76,9 @@ pub fn get_manager_config(
     // .map_err(|_| "Failed to parse config".into())
 }
 
-pub(crate) async fn set_manager_config(path: &str) -> Result<(), Box<dyn Error>>{
-    // Read the config
-    let config = get_manager_config(path)?;
-
+pub(crate) async fn set_manager_config(
+    config: valence_program_manager::config::Config,
+) -> Result<(), Box<dyn Error>> {
     // Set the global config of the manager with the read config
     let mut gc = valence_program_manager::config::GLOBAL_CONFIG.lock().await;
     *gc = config;
@@ -98,4 +109,4 @@ fn clone_config_from_repo(env_path: &str, config_path: &PathBuf) -> Result<(), B
     )?;
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,214 @@
+use bech32::{Bech32, Hrp};
+use bip32::{DerivationPath, XPrv};
+use bip39::Mnemonic;
+use chrono::Utc;
+use config::Config as ConfigHelper;
+use ripemd::Ripemd160;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, error::Error, path::Path};
+use valence_program_manager::{config::Config, program_config::ProgramConfig};
+
+use crate::{
+    manager_config::get_manager_config_hash,
+    preflight::get_required_code_ids,
+    program_config::program_fingerprint,
+    program_params::{get_params_files_hashes, ProgramParams},
+};
+
+/// Environment variable of the mnemonic the manager deploys with
+const MANAGER_MNEMONIC_ENV: &str = "MANAGER_MNEMONIC";
+/// Coin type of the cosmos addresses we can derive, Example: ethermint chains use 60 and another address format
+const COSMOS_COIN_TYPE: u64 = 118;
+pub(crate) const MANIFEST_FILE: &str = "deployment.json";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrateInfo {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitInfo {
+    pub commit: String,
+    /// Uncommitted changes outside of the program outputs and the deployments index
+    pub dirty: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentOutcome {
+    InProgress,
+    Success,
+    Failed,
+}
+
+/// How a deployment was produced, written to `deployment.json` in its output directory
+#[derive(Debug, Clone, Serialize)]
+pub struct DeploymentManifest {
+    pub target_env: String,
+    pub program: CrateInfo,
+    pub git: Option<GitInfo>,
+    pub manager_config_hash: String,
+    /// Sha256 of the params files used, keyed by file name
+    pub params_files: BTreeMap<String, String>,
+    pub params: Option<BTreeMap<String, String>>,
+    pub fingerprint: String,
+    pub code_ids: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Address of the manager on every domain of the program
+    pub deployer_addresses: BTreeMap<String, String>,
+    /// Why the address of the manager could not be derived, by domain
+    pub deployer_address_errors: BTreeMap<String, String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub outcome: DeploymentOutcome,
+    pub error: Option<String>,
+}
+
+impl DeploymentManifest {
+    pub(crate) fn new(
+        program_path: &Path,
+        target_env: &str,
+        program_config: &ProgramConfig,
+        program_params: Option<&ProgramParams>,
+        config: &Config,
+    ) -> Result<Self, Box<dyn Error>> {
+        let code_ids = get_required_code_ids(program_config)
+            .map_err(|errors| errors.join("\n"))?
+            .into_iter()
+            .map(|(domain, keys)| {
+                let ids = keys
+                    .into_iter()
+                    .filter_map(|key| {
+                        config
+                            .contracts
+                            .code_ids
+                            .get(&domain)
+                            .and_then(|ids| ids.get(&key))
+                            .map(|id| (key, *id))
+                    })
+                    .collect();
+                (domain, ids)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mnemonic = std::env::var(MANAGER_MNEMONIC_ENV)
+            .map_err(|_| format!("{} is not set", MANAGER_MNEMONIC_ENV));
+        let mut deployer_addresses = BTreeMap::new();
+        let mut deployer_address_errors = BTreeMap::new();
+        for domain in code_ids.keys() {
+            let address = mnemonic.clone().and_then(|mnemonic| {
+                let chain = config
+                    .chains
+                    .get(domain)
+                    .ok_or(format!("Chain {} not found in the manager config", domain))?;
+                derive_address(&mnemonic, chain.coin_type, &chain.prefix).map_err(|e| e.to_string())
+            });
+
+            match address {
+                Ok(address) => deployer_addresses.insert(domain.clone(), address),
+                Err(e) => deployer_address_errors.insert(domain.clone(), e),
+            };
+        }
+
+        let params_files = match program_params {
+            Some(_) => get_params_files_hashes(program_path, target_env)?,
+            None => BTreeMap::new(),
+        };
+
+        Ok(DeploymentManifest {
+            target_env: target_env.to_string(),
+            program: get_crate_info(program_path)?,
+            git: get_git_info(),
+            manager_config_hash: get_manager_config_hash(target_env)?,
+            params_files,
+            params: program_params.map(|p| p.to_sorted_map()),
+            fingerprint: program_fingerprint(program_config),
+            code_ids,
+            deployer_addresses,
+            deployer_address_errors,
+            started_at: Utc::now().to_rfc3339(),
+            finished_at: None,
+            outcome: DeploymentOutcome::InProgress,
+            error: None,
+        })
+    }
+
+    /// Record the end of the deployment, with the error if it failed
+    pub(crate) fn finish(&mut self, error: Option<String>) {
+        self.finished_at = Some(Utc::now().to_rfc3339());
+        self.outcome = match error {
+            Some(_) => DeploymentOutcome::Failed,
+            None => DeploymentOutcome::Success,
+        };
+        self.error = error;
+    }
+
+    pub(crate) fn write(&self, output_path: &Path) -> Result<(), Box<dyn Error>> {
+        std::fs::write(
+            output_path.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(self)?,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Name and version of the program crate, the version can be inherited from the workspace
+fn get_crate_info(program_path: &Path) -> Result<CrateInfo, Box<dyn Error>> {
+    let manifest = ConfigHelper::builder()
+        .add_source(config::File::from(program_path.join("Cargo.toml")))
+        .build()?;
+
+    let version = match manifest.get_string("package.version") {
+        Ok(version) => version,
+        Err(_) => ConfigHelper::builder()
+            .add_source(config::File::from(
+                std::env::current_dir()?.join("Cargo.toml"),
+            ))
+            .build()?
+            .get_string("workspace.package.version")?,
+    };
+
+    Ok(CrateInfo {
+        name: manifest.get_string("package.name")?,
+        version,
+    })
+}
+
+/// Current commit of the workspace, None if it is not a git repository
+fn get_git_info() -> Option<GitInfo> {
+    let commit = cmd_lib::run_fun!(git rev-parse HEAD).ok()?;
+    let status = cmd_lib::run_fun!(
+        git status --porcelain -- . ":(exclude)programs/*/output/*" ":(exclude)deployments"
+    )
+    .ok()?;
+
+    Some(GitInfo {
+        commit,
+        dirty: !status.trim().is_empty(),
+    })
+}
+
+/// Address of the first account of a mnemonic on a chain, Example: `m/44'/118'/0'/0/0`
+pub(crate) fn derive_address(
+    mnemonic: &str,
+    coin_type: u64,
+    prefix: &str,
+) -> Result<String, Box<dyn Error>> {
+    if coin_type != COSMOS_COIN_TYPE {
+        return Err(format!(
+            "Addresses of coin type {} can't be derived, only {} is supported",
+            coin_type, COSMOS_COIN_TYPE
+        )
+        .into());
+    }
+
+    let seed = Mnemonic::parse(mnemonic.trim())?.to_seed("");
+    let path = format!("m/44'/{}'/0'/0/0", coin_type).parse::<DerivationPath>()?;
+    let public_key = XPrv::derive_from_path(seed, &path)?.public_key().to_bytes();
+
+    let hash = Ripemd160::digest(Sha256::digest(public_key));
+
+    Ok(bech32::encode::<Bech32>(Hrp::parse(prefix)?, &hash)?)
+}
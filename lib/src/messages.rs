@@ -0,0 +1,53 @@
+use serde::Serialize;
+use std::error::Error;
+use valence_authorization_utils::authorization_message::{
+    Message, MessageDetails, MessageType, ParamRestriction,
+};
+
+use crate::program_config::get_variant_name;
+
+/// ExecuteMsg variant of the libraries that executes their functions, Example: `{"process_function": {"forward": {}}}`
+pub(crate) const PROCESS_FUNCTION: &str = "process_function";
+
+/// The `FunctionMsgs` of a library, the functions an authorization can restrict a message to
+pub trait LibraryFunction: Serialize {}
+
+impl LibraryFunction for valence_forwarder_library::msg::FunctionMsgs {}
+impl LibraryFunction for valence_splitter_library::msg::FunctionMsgs {}
+impl LibraryFunction for valence_reverse_splitter_library::msg::FunctionMsgs {}
+impl LibraryFunction for valence_astroport_lper::msg::FunctionMsgs {}
+impl LibraryFunction for valence_astroport_withdrawer::msg::FunctionMsgs {}
+impl LibraryFunction for valence_generic_ibc_transfer_library::msg::FunctionMsgs {}
+impl LibraryFunction for valence_neutron_ibc_transfer_library::msg::FunctionMsgs {}
+impl LibraryFunction for valence_osmosis_gamm_lper::msg::FunctionMsgs {}
+impl LibraryFunction for valence_osmosis_gamm_withdrawer::msg::FunctionMsgs {}
+impl LibraryFunction for valence_osmosis_cl_lper::msg::FunctionMsgs {}
+impl LibraryFunction for valence_osmosis_cl_withdrawer::msg::FunctionMsgs {}
+
+/// Restriction that only allows a function of a library, from its `FunctionMsgs` variant
+/// Example: `valence_forwarder_library::msg::FunctionMsgs::Forward {}` -> `MustBeIncluded(["process_function", "forward"])`
+pub fn process_function_restriction<T: LibraryFunction>(
+    function: &T,
+) -> Result<ParamRestriction, Box<dyn Error>> {
+    let name =
+        get_variant_name(function).ok_or("Library function should serialize to an enum variant")?;
+
+    Ok(ParamRestriction::MustBeIncluded(vec![
+        PROCESS_FUNCTION.to_string(),
+        name,
+    ]))
+}
+
+/// Message details of a function of a library, from its `FunctionMsgs` variant
+/// Example: `process_function_message(&valence_splitter_library::msg::FunctionMsgs::Split {})`
+pub fn process_function_message<T: LibraryFunction>(
+    function: &T,
+) -> Result<MessageDetails, Box<dyn Error>> {
+    Ok(MessageDetails {
+        message_type: MessageType::CosmwasmExecuteMsg,
+        message: Message {
+            name: PROCESS_FUNCTION.to_string(),
+            params_restrictions: Some(vec![process_function_restriction(function)?]),
+        },
+    })
+}
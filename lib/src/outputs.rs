@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+use valence_program_manager::program_config::ProgramConfig;
+
+use crate::{
+    manifest::{DeploymentOutcome, MANIFEST_FILE},
+    program_config::program_fingerprint,
+};
+
+/// Index of all deployments of the workspace, rebuilt after every deployment
+const INDEX_PATH: &str = "deployments/index.json";
+pub const RAW_CONFIG_FILE: &str = "raw-program-config.json";
+pub const INSTANTIATED_CONFIG_FILE: &str = "instantiated-program-config.json";
+const FINGERPRINT_FILE: &str = "raw-program-config.sha256";
+const PARAMS_FILE: &str = "program-params.json";
+
+/// A deployment of a program, found in `programs/<program>/output/<timestamp>/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentEntry {
+    /// `<program>/<timestamp>`
+    pub id: String,
+    pub program: String,
+    pub env: Option<String>,
+    pub timestamp: String,
+    pub fingerprint: Option<String>,
+    pub status: DeploymentOutcome,
+    /// Id of the program in the registry, set once instantiated
+    pub program_id: Option<u64>,
+    /// Output directory, relative to the workspace
+    pub path: PathBuf,
+}
+
+/// A deployment with its program configs
+#[derive(Debug, Clone)]
+pub struct Deployment {
+    pub entry: DeploymentEntry,
+    pub raw: Option<ProgramConfig>,
+    pub instantiated: Option<ProgramConfig>,
+}
+
+impl DeploymentEntry {
+    fn from_path(program: &str, path: PathBuf) -> Result<Self, Box<dyn Error>> {
+        let timestamp = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or(format!("Invalid output directory {}", path.display()))?
+            .to_string();
+        let instantiated = read_config(&path.join(INSTANTIATED_CONFIG_FILE))?;
+
+        // Deployments before the manifest are complete once instantiated
+        let status = read_json(&path.join(MANIFEST_FILE))
+            .and_then(|manifest| serde_json::from_value(manifest["outcome"].clone()).ok())
+            .unwrap_or(match instantiated {
+                Some(_) => DeploymentOutcome::Success,
+                None => DeploymentOutcome::Failed,
+            });
+
+        Ok(DeploymentEntry {
+            id: format!("{}/{}", program, timestamp),
+            program: program.to_string(),
+            env: read_json(&path.join(PARAMS_FILE))
+                .and_then(|params| params["target_env"].as_str().map(|env| env.to_string())),
+            timestamp,
+            fingerprint: get_fingerprint(&path)?,
+            status,
+            program_id: instantiated.map(|config| config.id),
+            path,
+        })
+    }
+
+    pub fn is_instantiated(&self) -> bool {
+        self.path.join(INSTANTIATED_CONFIG_FILE).exists()
+    }
+
+    pub fn raw_config_path(&self) -> PathBuf {
+        self.path.join(RAW_CONFIG_FILE)
+    }
+
+    pub fn instantiated_config_path(&self) -> PathBuf {
+        self.path.join(INSTANTIATED_CONFIG_FILE)
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// Read a program config of an output directory, None if the file doesn't exist
+fn read_config(path: &Path) -> Result<Option<ProgramConfig>, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    let config = serde_json::from_str::<ProgramConfig>(&content)
+        .map_err(|e| format!("Failed to parse {} : {}", path.display(), e))?;
+
+    Ok(Some(config))
+}
+
+/// Fingerprint of the raw config of an output directory, computed if it was not recorded
+fn get_fingerprint(path: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    if let Ok(content) = std::fs::read_to_string(path.join(FINGERPRINT_FILE)) {
+        return Ok(content.split_whitespace().next().map(|f| f.to_string()));
+    }
+
+    Ok(read_config(&path.join(RAW_CONFIG_FILE))?.map(|config| program_fingerprint(&config)))
+}
+
+/// All deployments of a program, oldest first
+pub fn list(program: &str) -> Result<Vec<DeploymentEntry>, Box<dyn Error>> {
+    let output_path = PathBuf::from("programs").join(program).join("output");
+
+    if !output_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut entries = std::fs::read_dir(&output_path)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        // A malformed or partial output directory shouldn't hide the other deployments
+        .filter_map(
+            |path| match DeploymentEntry::from_path(program, path.clone()) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    eprintln!("Warning: skipping output {} : {}", path.display(), e);
+                    None
+                }
+            },
+        )
+        .collect::<Vec<_>>();
+
+    // Output directories are named by timestamp
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    Ok(entries)
+}
+
+/// All deployments of every program of the workspace
+pub fn list_all() -> Result<Vec<DeploymentEntry>, Box<dyn Error>> {
+    let mut entries = vec![];
+
+    for program in std::fs::read_dir("programs")? {
+        let program = program?;
+        if program.path().is_dir() {
+            entries.extend(list(&program.file_name().to_string_lossy())?);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Latest instantiated deployment of a program on an env
+pub fn latest(program: &str, env: &str) -> Result<Option<DeploymentEntry>, Box<dyn Error>> {
+    Ok(list(program)?
+        .into_iter()
+        .rfind(|entry| entry.env.as_deref() == Some(env) && entry.is_instantiated()))
+}
+
+/// Load a deployment by id, Example: `astroport_lper/2025-01-01_12:00:00`
+pub fn load(id: &str) -> Result<Deployment, Box<dyn Error>> {
+    let (program, timestamp) = id.split_once('/').ok_or(format!(
+        "Invalid deployment id {}, expected <program>/<timestamp>",
+        id
+    ))?;
+
+    let path = PathBuf::from("programs")
+        .join(program)
+        .join("output")
+        .join(timestamp);
+
+    if !path.is_dir() {
+        return Err(format!("Deployment {} not found", id).into());
+    }
+
+    let entry = DeploymentEntry::from_path(program, path)?;
+
+    Ok(Deployment {
+        raw: read_config(&entry.raw_config_path())?,
+        instantiated: read_config(&entry.instantiated_config_path())?,
+        entry,
+    })
+}
+
+/// Rebuild `deployments/index.json` from the output directories of all programs
+pub fn write_index() -> Result<(), Box<dyn Error>> {
+    let index_path = PathBuf::from(INDEX_PATH);
+
+    if let Some(parent) = index_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(
+        index_path,
+        serde_json::to_string_pretty(&serde_json::json!({ "deployments": list_all()? }))?,
+    )?;
+
+    Ok(())
+}
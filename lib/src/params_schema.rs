@@ -0,0 +1,228 @@
+use config::Config as ConfigHelper;
+use serde::Deserialize;
+use std::{collections::BTreeMap, error::Error, fmt, path::Path};
+use valence_program_manager::config::Config;
+
+use crate::{
+    address::{validate_domain_address, MAIN_DOMAIN},
+    assets::{parse_amount, AssetRegistry},
+    program_params::ProgramParams,
+};
+
+/// File in `program_params/` where the program declares the params it reads
+const SCHEMA_FILE: &str = "schema.toml";
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamType {
+    #[default]
+    String,
+    Integer,
+    Decimal,
+    Bool,
+    /// Bech32 address on the domain of the param (main domain by default)
+    Address,
+    /// Amount in base units or with an asset symbol, Example: `"1000 OSMO"`
+    Amount,
+}
+
+impl ParamType {
+    fn validate(
+        &self,
+        value: &str,
+        domain: &str,
+        config: &Config,
+        assets: &AssetRegistry,
+    ) -> Result<(), String> {
+        let valid = match self {
+            ParamType::String => true,
+            ParamType::Amount => return parse_amount(value, assets).map(|_| ()),
+            ParamType::Address => {
+                return validate_domain_address(config, value, domain).map(|_| ())
+            }
+            ParamType::Integer => value.parse::<u128>().is_ok(),
+            ParamType::Decimal => {
+                let (int, frac) = value.split_once('.').unwrap_or((value, ""));
+                !int.is_empty()
+                    && int.chars().all(|c| c.is_ascii_digit())
+                    && frac.chars().all(|c| c.is_ascii_digit())
+            }
+            ParamType::Bool => value.parse::<bool>().is_ok(),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("expected {}, got {:?}", self, value))
+        }
+    }
+}
+
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ParamType::String => "string",
+            ParamType::Integer => "integer",
+            ParamType::Decimal => "decimal",
+            ParamType::Bool => "bool",
+            ParamType::Address => "address",
+            ParamType::Amount => "amount",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParamSpec {
+    #[serde(rename = "type", default)]
+    pub ty: ParamType,
+    #[serde(default)]
+    pub description: String,
+    pub default: Option<String>,
+    /// Params without a default are required unless set otherwise
+    pub required: Option<bool>,
+    /// Domain the value belongs to, used to validate addresses
+    pub domain: Option<String>,
+}
+
+impl ParamSpec {
+    pub fn is_required(&self) -> bool {
+        self.required.unwrap_or(self.default.is_none())
+    }
+
+    fn domain(&self) -> &str {
+        self.domain.as_deref().unwrap_or(MAIN_DOMAIN)
+    }
+}
+
+/// The params a program declares in `program_params/schema.toml`, keyed by param name
+#[derive(Debug, Clone)]
+pub struct ParamsSchema(BTreeMap<String, ParamSpec>);
+
+pub fn get_params_schema(program_path: &Path) -> Result<Option<ParamsSchema>, Box<dyn Error>> {
+    let schema_path = std::env::current_dir()?
+        .join(program_path)
+        .join("program_params")
+        .join(SCHEMA_FILE);
+
+    if !schema_path.exists() {
+        return Ok(None);
+    }
+
+    let specs = ConfigHelper::builder()
+        .add_source(config::File::from(schema_path))
+        .build()?
+        .try_deserialize::<BTreeMap<String, ParamSpec>>()
+        .map_err(|e| format!("Failed to parse params schema : {}", e))?;
+
+    Ok(Some(ParamsSchema(specs)))
+}
+
+impl ParamsSchema {
+    /// Domain of a param, the main domain if it is not declared
+    pub(crate) fn domain(&self, key: &str) -> &str {
+        self.0.get(key).map_or(MAIN_DOMAIN, |spec| spec.domain())
+    }
+
+    /// Set the default value of every declared param that wasn't provided
+    pub fn apply_defaults(&self, params: &mut ProgramParams) {
+        for (key, spec) in self.0.iter() {
+            if let Some(default) = &spec.default {
+                params.set_if_missing(key, default);
+            }
+        }
+    }
+
+    /// Check all params against the schema in a single pass
+    pub fn check(&self, params: &ProgramParams, config: &Config) -> ParamsReport {
+        let mut report = ParamsReport::default();
+        let values = params.to_sorted_map();
+
+        for (key, spec) in self.0.iter() {
+            match values.get(key) {
+                Some(value) => {
+                    if let Err(e) =
+                        spec.ty
+                            .validate(value, spec.domain(), config, params.asset_registry())
+                    {
+                        report.mistyped.push((key.clone(), e));
+                    }
+                }
+                None if spec.is_required() => report.missing.push(key.clone()),
+                None => (),
+            }
+        }
+
+        report.unused = values
+            .keys()
+            .filter(|key| !self.0.contains_key(*key))
+            .cloned()
+            .collect();
+
+        report
+    }
+
+    /// Markdown table of the declared params, to be included in the program README
+    pub fn to_markdown(&self) -> String {
+        let mut table = String::from(
+            "| Param | Type | Required | Default | Description |\n|---|---|---|---|---|\n",
+        );
+
+        for (key, spec) in self.0.iter() {
+            table.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} |\n",
+                key,
+                match spec.ty {
+                    ParamType::Address => format!("{} ({})", spec.ty, spec.domain()),
+                    _ => spec.ty.to_string(),
+                },
+                if spec.is_required() { "yes" } else { "no" },
+                spec.default
+                    .as_ref()
+                    .map(|d| format!("`{}`", d))
+                    .unwrap_or_default(),
+                spec.description.replace('|', "\\|"),
+            ));
+        }
+
+        table
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ParamsReport {
+    pub missing: Vec<String>,
+    /// (key, reason)
+    pub mistyped: Vec<(String, String)>,
+    pub unused: Vec<String>,
+}
+
+impl ParamsReport {
+    /// Missing and mistyped params fail the deployment, unused params are only a warning
+    pub fn has_errors(&self) -> bool {
+        !self.missing.is_empty() || !self.mistyped.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.has_errors() && self.unused.is_empty()
+    }
+}
+
+impl fmt::Display for ParamsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "All params are valid");
+        }
+
+        for key in self.missing.iter() {
+            writeln!(f, "missing: {}", key)?;
+        }
+        for (key, reason) in self.mistyped.iter() {
+            writeln!(f, "mistyped: {} ({})", key, reason)?;
+        }
+        for key in self.unused.iter() {
+            writeln!(f, "unused: {}", key)?;
+        }
+        Ok(())
+    }
+}
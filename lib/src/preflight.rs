@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use valence_program_manager::{config::Config, program_config::ProgramConfig};
+
+use crate::{
+    address::MAIN_DOMAIN,
+    program_config::{get_authorization_functions, get_function_chain_name, get_variant_name},
+};
+
+const AUTHORIZATION_CODE_ID: &str = "valence_authorization";
+const PROCESSOR_CODE_ID: &str = "valence_processor";
+const POLYTONE_PROXY_CODE_ID: &str = "polytone_proxy";
+
+/// Code id keys (in `contracts.code_ids.<domain>`) needed to instantiate the program, keyed by domain
+pub fn get_required_code_ids(
+    program_config: &ProgramConfig,
+) -> Result<BTreeMap<String, BTreeSet<String>>, Vec<String>> {
+    let mut code_ids: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut errors = vec![];
+
+    // The authorization contract and a processor always live on the main domain
+    code_ids
+        .entry(MAIN_DOMAIN.to_string())
+        .or_default()
+        .extend([
+            AUTHORIZATION_CODE_ID.to_string(),
+            PROCESSOR_CODE_ID.to_string(),
+        ]);
+
+    for account in program_config.accounts.values() {
+        match get_variant_name(&account.ty).as_deref() {
+            // Existing accounts are not instantiated
+            Some("addr") => (),
+            Some(ty) => {
+                code_ids
+                    .entry(account.domain.get_chain_name().to_string())
+                    .or_default()
+                    .insert(format!("valence_{}_account", ty));
+            }
+            None => errors.push(format!("account {}: unknown account type", account.name)),
+        }
+    }
+
+    for library in program_config.libraries.values() {
+        match get_variant_name(&library.config).as_deref() {
+            Some("none") | None => {
+                errors.push(format!("library {}: has no library config", library.name))
+            }
+            Some(ty) => {
+                code_ids
+                    .entry(library.domain.get_chain_name().to_string())
+                    .or_default()
+                    .insert(ty.to_string());
+            }
+        }
+    }
+
+    // Every domain an authorization executes on needs a processor
+    for authorization in program_config.authorizations.iter() {
+        for function in get_authorization_functions(authorization) {
+            code_ids
+                .entry(get_function_chain_name(function.domain).to_string())
+                .or_default()
+                .insert(PROCESSOR_CODE_ID.to_string());
+        }
+    }
+
+    // The main domain reaches the other domains through a polytone proxy
+    for (domain, keys) in code_ids.iter_mut() {
+        if domain != MAIN_DOMAIN {
+            keys.insert(POLYTONE_PROXY_CODE_ID.to_string());
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(code_ids)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Check offline that the manager config has everything needed to deploy the program:
+/// the chain info and a code id for every contract on each domain, and a polytone bridge
+/// from the main domain to every other domain
+pub fn preflight_check(program_config: &ProgramConfig, config: &Config) -> Vec<String> {
+    let code_ids = match get_required_code_ids(program_config) {
+        Ok(code_ids) => code_ids,
+        Err(errors) => return errors,
+    };
+
+    let mut errors = vec![];
+
+    for (domain, keys) in code_ids.iter() {
+        if !config.chains.contains_key(domain) {
+            errors.push(format!("domain {}: chain not found in chains", domain));
+        }
+
+        let domain_code_ids = config.contracts.code_ids.get(domain);
+        for key in keys {
+            if !domain_code_ids.is_some_and(|ids| ids.contains_key(key)) {
+                errors.push(format!(
+                    "domain {}: code id {} not found in contracts.code_ids.{}",
+                    domain, key, domain
+                ));
+            }
+        }
+
+        if domain != MAIN_DOMAIN
+            && !config
+                .bridges
+                .get(MAIN_DOMAIN)
+                .is_some_and(|bridges| bridges.contains_key(domain))
+        {
+            errors.push(format!(
+                "domain {}: polytone bridge not found in bridges.{}.{}",
+                domain, MAIN_DOMAIN, domain
+            ));
+        }
+    }
+
+    errors
+}
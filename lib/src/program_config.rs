@@ -1,6 +1,120 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use valence_authorization_utils::{
+    authorization::{AuthorizationInfo, Subroutine},
+    authorization_message::MessageDetails,
+    domain::Domain,
+};
+use valence_library_utils::LibraryAccountType;
 use valence_program_manager::program_config::ProgramConfig;
 
+use crate::address::MAIN_DOMAIN;
+
+/// Snake case name of the variant of a serialized enum, Example: `ValenceForwarderLibrary(..)` -> `valence_forwarder_library`
+pub(crate) fn get_variant_name<T: serde::Serialize>(value: &T) -> Option<String> {
+    let name = match serde_json::to_value(value).ok()? {
+        serde_json::Value::String(name) => name,
+        serde_json::Value::Object(map) if map.len() == 1 => map.keys().next()?.clone(),
+        _ => return None,
+    };
+
+    Some(to_snake_case(&name))
+}
+
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+
+    snake
+}
+
 pub(crate) fn read_program_config_from_json(path: &str) -> ProgramConfig {
     let content = std::fs::read_to_string(path).expect("Unable to open program config file");
     serde_json::from_str::<ProgramConfig>(&content).expect("Failed to parse into ProgramConfig")
-}
\ No newline at end of file
+}
+
+/// Sort the keys of every object, so the output doesn't depend on the map ordering of serde_json
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_keys(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+/// Canonical serialization of a program config: sorted keys, pretty printed with a trailing new line
+pub fn canonical_program_config(program_config: &ProgramConfig) -> String {
+    let value = serde_json::to_value(program_config).expect("Failed to serialize program config");
+    let mut content = serde_json::to_string_pretty(&sort_keys(value))
+        .expect("Failed to serialize program config");
+    content.push('\n');
+
+    content
+}
+
+/// Sha256 of the canonical serialization of a program config, as hex
+pub fn program_fingerprint(program_config: &ProgramConfig) -> String {
+    hex::encode(Sha256::digest(
+        canonical_program_config(program_config).as_bytes(),
+    ))
+}
+
+/// A function of an authorization subroutine, atomic or non atomic
+pub(crate) struct FunctionInfo<'a> {
+    pub domain: &'a Domain,
+    pub message_details: &'a MessageDetails,
+    pub contract_address: &'a LibraryAccountType,
+}
+
+pub(crate) fn get_authorization_functions(
+    authorization: &AuthorizationInfo,
+) -> Vec<FunctionInfo<'_>> {
+    match &authorization.subroutine {
+        Subroutine::Atomic(subroutine) => subroutine
+            .functions
+            .iter()
+            .map(|f| FunctionInfo {
+                domain: &f.domain,
+                message_details: &f.message_details,
+                contract_address: &f.contract_address,
+            })
+            .collect(),
+        Subroutine::NonAtomic(subroutine) => subroutine
+            .functions
+            .iter()
+            .map(|f| FunctionInfo {
+                domain: &f.domain,
+                message_details: &f.message_details,
+                contract_address: &f.contract_address,
+            })
+            .collect(),
+    }
+}
+
+/// Chain name of an authorization domain, the main domain is where the authorization contract lives
+pub(crate) fn get_function_chain_name(domain: &Domain) -> &str {
+    match domain {
+        Domain::Main => MAIN_DOMAIN,
+        Domain::External(chain) => chain,
+    }
+}
@@ -1,41 +1,270 @@
 use config::Config as ConfigHelper;
-use std::{collections::HashMap, error::Error, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
+use crate::{
+    assets::{parse_amount, AssetRegistry},
+    helpers::hash_file,
+    references::ResolvedReference,
+};
+
+/// Params shared by all environments, merged underneath the env file
+const COMMON_PARAMS_FILE: &str = "common";
+/// Key that allows a params file to extend another params file
+const EXTENDS_KEY: &str = "extends";
+/// Prefix of environment variables that override params, Example: `DEPLOY_PARAM_OWNER`
+pub(crate) const ENV_PARAMS_PREFIX: &str = "DEPLOY_PARAM";
+
+/// Read the params of the program for the env, layered from lowest to highest priority:
+/// common.toml, extended files, env file, `DEPLOY_PARAM_<KEY>` env vars, cli overrides.
 pub fn get_program_params(
-    program_path: &PathBuf,
+    program_path: &Path,
+    env: &str,
+    overrides: &[(String, String)],
+) -> Result<ProgramParams, Box<dyn Error>> {
+    read_program_params(program_path, env, overrides, true)
+}
+
+/// Read the params of the program for the env from its files only, the overrides
+/// of the env vars are meant for the program being deployed, not for its dependencies
+pub(crate) fn get_program_file_params(
+    program_path: &Path,
+    env: &str,
+) -> Result<ProgramParams, Box<dyn Error>> {
+    read_program_params(program_path, env, &[], false)
+}
+
+fn read_program_params(
+    program_path: &Path,
     env: &str,
+    overrides: &[(String, String)],
+    env_overrides: bool,
 ) -> Result<ProgramParams, Box<dyn Error>> {
     let params_path = std::env::current_dir()?
         .join(program_path)
         .join("program_params");
-    let params_path_str = params_path
-        .to_str()
-        .expect("Params path should be a string");
-
-    let params = match ConfigHelper::builder()
-        .add_source(config::File::with_name(&format!(
-            "{}/{}.toml",
-            params_path_str, env
-        )))
-        .build()
-    {
+
+    // Files from the most specific (env) to the most generic one
+    let files = get_params_files(&params_path, env)?;
+
+    // common.toml is optional and always sits underneath everything else
+    let mut builder = ConfigHelper::builder();
+
+    if !files.iter().any(|(name, _)| name == COMMON_PARAMS_FILE) {
+        builder = builder.add_source(
+            config::File::from(params_file_path(&params_path, COMMON_PARAMS_FILE)).required(false),
+        );
+    }
+
+    for (_, path) in files.iter().rev() {
+        builder = builder.add_source(config::File::from(path.clone()));
+    }
+
+    if env_overrides {
+        builder = builder.add_source(config::Environment::with_prefix(ENV_PARAMS_PREFIX));
+    }
+
+    for (key, value) in overrides {
+        builder = builder.set_override(key, value.as_str())?;
+    }
+
+    let mut params = match builder.build() {
         Ok(cfg) => cfg
             .try_deserialize::<HashMap<String, String>>()
             .map_err(|e| format!("Failed to parse program params : {}", e)),
         Err(_) => Err("Failed to parse program params".to_string()),
     }?;
 
-    Ok(ProgramParams(params))
+    params.remove(EXTENDS_KEY);
+
+    Ok(ProgramParams {
+        params,
+        accessed: Arc::default(),
+        assets: AssetRegistry::default(),
+        references: BTreeMap::new(),
+    })
+}
+
+/// Follow the `extends` keys starting from the env file, returns (name, path) pairs
+fn get_params_files(
+    params_path: &Path,
+    env: &str,
+) -> Result<Vec<(String, PathBuf)>, Box<dyn Error>> {
+    let mut files: Vec<(String, PathBuf)> = vec![];
+    let mut next = Some(env.to_string());
+
+    while let Some(name) = next {
+        if files.iter().any(|(n, _)| *n == name) {
+            let chain = files
+                .iter()
+                .map(|(n, _)| n.as_str())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(
+                format!("Params files extend each other in a loop: {chain} -> {name}").into(),
+            );
+        }
+
+        let path = params_file_path(params_path, &name);
+
+        if !path.exists() {
+            return Err(format!("Program params file {} does not exist", path.display()).into());
+        }
+
+        next = ConfigHelper::builder()
+            .add_source(config::File::from(path.clone()))
+            .build()
+            .map_err(|e| {
+                format!(
+                    "Failed to parse program params file {} : {}",
+                    path.display(),
+                    e
+                )
+            })?
+            .get_string(EXTENDS_KEY)
+            .ok();
+
+        files.push((name, path));
+    }
+
+    Ok(files)
+}
+
+/// Sha256 of every params file used for the env, keyed by file name
+pub(crate) fn get_params_files_hashes(
+    program_path: &Path,
+    env: &str,
+) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let params_path = std::env::current_dir()?
+        .join(program_path)
+        .join("program_params");
+
+    let mut files = get_params_files(&params_path, env)?;
+    files.push((
+        COMMON_PARAMS_FILE.to_string(),
+        params_file_path(&params_path, COMMON_PARAMS_FILE),
+    ));
+
+    let mut hashes = BTreeMap::new();
+    for (name, path) in files.into_iter().filter(|(_, path)| path.exists()) {
+        hashes.insert(format!("{}.toml", name), hash_file(&path)?);
+    }
+
+    Ok(hashes)
+}
+
+fn params_file_path(params_path: &Path, name: &str) -> PathBuf {
+    params_path.join(format!("{}.toml", name))
 }
 
-#[derive(Debug)]
-pub struct ProgramParams(HashMap<String, String>);
+/// Parse a `key=value` param override passed from the cli
+pub(crate) fn parse_param_override(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s.split_once('=').ok_or(format!(
+        "Invalid param override \"{}\", expected key=value",
+        s
+    ))?;
+
+    if key.trim().is_empty() {
+        return Err(format!("Invalid param override \"{}\", key is empty", s));
+    }
+
+    Ok((key.trim().to_string(), value.to_string()))
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgramParams {
+    params: HashMap<String, String>,
+    // Keys read by the builder, shared between clones to report unused params after the build
+    accessed: Arc<Mutex<BTreeSet<String>>>,
+    // Asset registry of the environment, used to convert amounts
+    assets: AssetRegistry,
+    // Params resolved from other deployments, keyed by param
+    references: BTreeMap<String, ResolvedReference>,
+}
 
 impl ProgramParams {
+    /// All params sorted by key
+    pub(crate) fn to_sorted_map(&self) -> BTreeMap<String, String> {
+        self.params.clone().into_iter().collect()
+    }
+
+    pub(crate) fn set_asset_registry(&mut self, assets: AssetRegistry) {
+        self.assets = assets;
+    }
+
+    pub(crate) fn asset_registry(&self) -> &AssetRegistry {
+        &self.assets
+    }
+
+    pub(crate) fn add_reference(&mut self, key: &str, reference: ResolvedReference) {
+        self.references.insert(key.to_string(), reference);
+    }
+
+    pub(crate) fn references(&self) -> &BTreeMap<String, ResolvedReference> {
+        &self.references
+    }
+
+    pub(crate) fn set(&mut self, key: &str, value: &str) {
+        self.params.insert(key.to_string(), value.to_string());
+    }
+
+    pub(crate) fn set_if_missing(&mut self, key: &str, value: &str) {
+        self.params
+            .entry(key.to_string())
+            .or_insert_with(|| value.to_string());
+    }
+
+    /// Params that were never read with `get`
+    pub(crate) fn unused_keys(&self) -> Vec<String> {
+        let accessed = self.accessed.lock().unwrap();
+
+        self.to_sorted_map()
+            .into_keys()
+            .filter(|key| !accessed.contains(key))
+            .collect()
+    }
+
     pub fn get(&self, key: &str) -> String {
-        self.0
+        self.accessed.lock().unwrap().insert(key.to_string());
+
+        self.params
             .get(key)
-            .expect(format!("Key {} not found", key).as_str())
+            .unwrap_or_else(|| panic!("Key {} not found", key))
             .to_string()
     }
+
+    /// Get an amount in base units, Example: `"1000 OSMO"` or `"1000000000"` both return 1000000000
+    pub fn get_amount(&self, key: &str) -> u128 {
+        parse_amount(&self.get(key), &self.assets)
+            .unwrap_or_else(|e| panic!("Param {}: {}", key, e))
+            .0
+    }
+
+    /// Get the denom on the chain and the amount in base units of an amount with a symbol, Example: `"0.5 ATOM"`
+    pub fn get_coin(&self, key: &str, chain: &str) -> (String, u128) {
+        let (amount, asset) = parse_amount(&self.get(key), &self.assets)
+            .unwrap_or_else(|e| panic!("Param {}: {}", key, e));
+        let asset = asset.unwrap_or_else(|| panic!("Param {}: amount has no asset symbol", key));
+        let denom = self
+            .assets
+            .denom_on(asset, chain)
+            .unwrap_or_else(|e| panic!("Param {}: {}", key, e));
+
+        (denom, amount)
+    }
+}
+
+// Print the effective params sorted by key, in the same format as the params files
+impl fmt::Display for ProgramParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in self.to_sorted_map() {
+            writeln!(f, "{} = {:?}", key, value)?;
+        }
+        Ok(())
+    }
 }
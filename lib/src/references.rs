@@ -0,0 +1,223 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::{error::Error, path::PathBuf};
+use valence_program_manager::program_config::ProgramConfig;
+
+use crate::{
+    outputs::{self, DeploymentEntry},
+    program_config::read_program_config_from_json,
+    program_params::ProgramParams,
+};
+
+/// Params prefix that references another deployment, Example: `ref:astroport_lper@mainnet:accounts.output_account`
+const REFERENCE_PARAM_PREFIX: &str = "ref:";
+
+/// A reference to a value of another program deployment: `ref:<program>@<env>[#<timestamp>]:<path>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramReference {
+    pub program: String,
+    pub env: String,
+    /// Pinned deployment, the latest deployment of the env is used if not set
+    pub timestamp: Option<String>,
+    pub path: String,
+}
+
+/// A resolved reference, recorded in the output of the deployment that used it
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedReference {
+    pub reference: String,
+    pub value: String,
+    /// Output directory of the referenced deployment
+    pub source: String,
+}
+
+impl ProgramReference {
+    /// Parse a param value, returns None if the value is not a reference
+    pub fn parse(value: &str) -> Option<Result<Self, String>> {
+        let reference = value.strip_prefix(REFERENCE_PARAM_PREFIX)?;
+        let invalid = || {
+            format!(
+                "Invalid reference {}, expected ref:<program>@<env>[#<timestamp>]:<path>",
+                value
+            )
+        };
+
+        // The timestamp contains ':' so the path is everything after the last one
+        let parse = || {
+            let (deployment, path) = reference.rsplit_once(':')?;
+            let (program, env) = deployment.split_once('@')?;
+            let (env, timestamp) = match env.split_once('#') {
+                Some((env, timestamp)) => (env, Some(timestamp.to_string())),
+                None => (env, None),
+            };
+
+            if program.is_empty() || env.is_empty() || path.is_empty() {
+                return None;
+            }
+
+            Some(ProgramReference {
+                program: program.to_string(),
+                env: env.to_string(),
+                timestamp,
+                path: path.to_string(),
+            })
+        };
+
+        Some(parse().ok_or_else(invalid))
+    }
+
+    /// Find the referenced deployment, the latest instantiated one of the env if not pinned
+    fn find_deployment(&self) -> Result<DeploymentEntry, Box<dyn Error>> {
+        let deployment = match &self.timestamp {
+            Some(timestamp) => outputs::list(&self.program)?.into_iter().find(|entry| {
+                entry.timestamp == *timestamp
+                    && entry.env.as_deref() == Some(self.env.as_str())
+                    && entry.is_instantiated()
+            }),
+            None => outputs::latest(&self.program, &self.env)?,
+        };
+
+        deployment.ok_or_else(|| {
+            format!(
+                "No instantiated deployment of {} found for env {}{}",
+                self.program,
+                self.env,
+                self.timestamp
+                    .as_ref()
+                    .map(|t| format!(" at {}", t))
+                    .unwrap_or_default()
+            )
+            .into()
+        })
+    }
+
+    pub fn resolve(&self) -> Result<(String, PathBuf), Box<dyn Error>> {
+        let deployment = self.find_deployment()?;
+        let program_config = read_program_config_from_json(
+            deployment
+                .instantiated_config_path()
+                .to_str()
+                .expect("Output path should be a string"),
+        );
+
+        let value = get_config_value(&program_config, &self.path).ok_or(format!(
+            "{} not found in deployment {}",
+            self.path, deployment.id
+        ))?;
+
+        Ok((value, deployment.path))
+    }
+}
+
+/// Get a value of an instantiated program config by path,
+/// accounts and libraries are found by label, Example: `accounts.output_account`
+fn get_config_value(program_config: &ProgramConfig, path: &str) -> Option<String> {
+    match path.split_once('.') {
+        Some(("accounts", label)) => program_config
+            .accounts
+            .values()
+            .find(|account| account.name == label)
+            .and_then(|account| account.addr.clone()),
+        Some(("libraries", label)) => program_config
+            .libraries
+            .values()
+            .find(|library| library.name == label)
+            .and_then(|library| library.addr.clone()),
+        // Any other field of the config, Example: `authorization_data.authorization_addr`
+        _ => {
+            let mut value = serde_json::to_value(program_config).ok()?;
+
+            for key in path.split('.') {
+                value = value.get(key)?.clone();
+            }
+
+            match value {
+                Value::String(s) => Some(s),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Resolve `ref:` params into values of other deployments
+pub(crate) fn resolve_reference_params(params: &mut ProgramParams) -> Result<(), Box<dyn Error>> {
+    for (key, value) in params.to_sorted_map() {
+        let Some(reference) = ProgramReference::parse(&value) else {
+            continue;
+        };
+
+        let (resolved, deployment) = reference
+            .map_err(|e| format!("Param {}: {}", key, e))?
+            .resolve()
+            .map_err(|e| format!("Param {}: {}", key, e))?;
+
+        params.set(&key, &resolved);
+        params.add_reference(
+            &key,
+            ResolvedReference {
+                reference: value,
+                value: resolved,
+                source: deployment.display().to_string(),
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProgramReference;
+
+    #[test]
+    fn parse_reference_to_latest_deployment() {
+        assert_eq!(
+            ProgramReference::parse("ref:astroport_lper@mainnet:accounts.output_account"),
+            Some(Ok(ProgramReference {
+                program: "astroport_lper".to_string(),
+                env: "mainnet".to_string(),
+                timestamp: None,
+                path: "accounts.output_account".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_reference_with_pinned_timestamp() {
+        // The timestamp contains ':', the path starts after the last one
+        assert_eq!(
+            ProgramReference::parse(
+                "ref:astroport_lper@mainnet#2025-01-01_10:00:00:accounts.output_account"
+            ),
+            Some(Ok(ProgramReference {
+                program: "astroport_lper".to_string(),
+                env: "mainnet".to_string(),
+                timestamp: Some("2025-01-01_10:00:00".to_string()),
+                path: "accounts.output_account".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_invalid_reference() {
+        assert!(matches!(
+            ProgramReference::parse("ref:astroport_lper:accounts.output_account"),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            ProgramReference::parse("ref:astroport_lper@mainnet:"),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            ProgramReference::parse("ref:@mainnet:accounts.output_account"),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn parse_value_that_is_not_a_reference() {
+        assert_eq!(ProgramReference::parse("neutron1abc"), None);
+        assert_eq!(ProgramReference::parse("asset:atom@neutron"), None);
+    }
+}
@@ -0,0 +1,351 @@
+use clap::ValueEnum;
+use serde_json::Value;
+use valence_authorization_utils::{
+    authorization::{AuthorizationModeInfo, PermissionTypeInfo},
+    authorization_message::ParamRestriction,
+};
+use valence_library_utils::LibraryAccountType;
+use valence_program_manager::{
+    account::AccountType, config::Config, program_config::ProgramConfig,
+};
+
+use crate::{
+    preflight::get_required_code_ids,
+    program_config::{get_authorization_functions, get_function_chain_name, get_variant_name},
+    program_params::ProgramParams,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// Name of the report in the output directory of a deployment
+    pub fn file_name(&self) -> &str {
+        match self {
+            ReportFormat::Markdown => "deployment-report.md",
+            ReportFormat::Html => "deployment-report.html",
+        }
+    }
+}
+
+/// A table of the report, cells with several values are separated by new lines
+struct Table {
+    title: &'static str,
+    headers: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+/// Report of a program config (raw or instantiated) for operators and auditors
+pub fn program_report(
+    program_config: &ProgramConfig,
+    program_params: Option<&ProgramParams>,
+    target_env: &str,
+    config: &Config,
+    format: ReportFormat,
+) -> String {
+    let summary = vec![
+        vec!["Environment".to_string(), target_env.to_string()],
+        vec!["Owner".to_string(), program_config.owner.clone()],
+        vec![
+            "Authorization".to_string(),
+            program_config.authorization_data.authorization_addr.clone(),
+        ],
+        vec![
+            "Processors".to_string(),
+            program_config
+                .authorization_data
+                .processor_addrs
+                .iter()
+                .map(|(domain, addr)| format!("{}: {}", domain, addr))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ],
+    ];
+
+    let mut tables = vec![
+        Table {
+            title: "Summary",
+            headers: &["Field", "Value"],
+            rows: summary,
+        },
+        Table {
+            title: "Accounts",
+            headers: &["Name", "Domain", "Type", "Address"],
+            rows: account_rows(program_config),
+        },
+        Table {
+            title: "Libraries",
+            headers: &["Name", "Domain", "Library", "Config", "Address"],
+            rows: library_rows(program_config),
+        },
+        Table {
+            title: "Authorizations",
+            headers: &[
+                "Label",
+                "Mode",
+                "Permissioned addresses",
+                "Functions",
+                "Restrictions",
+            ],
+            rows: authorization_rows(program_config),
+        },
+        Table {
+            title: "Code IDs",
+            headers: &["Domain", "Contract", "Code ID"],
+            rows: code_id_rows(program_config, config),
+        },
+    ];
+
+    if let Some(program_params) = program_params {
+        tables.push(Table {
+            title: "Params",
+            headers: &["Param", "Value"],
+            rows: program_params
+                .to_sorted_map()
+                .into_iter()
+                .map(|(key, value)| vec![key, value])
+                .collect(),
+        });
+    }
+
+    let title = format!("Program {}", program_config.name);
+
+    match format {
+        ReportFormat::Markdown => to_markdown(&title, &tables),
+        ReportFormat::Html => to_html(&title, &tables),
+    }
+}
+
+fn account_rows(program_config: &ProgramConfig) -> Vec<Vec<String>> {
+    program_config
+        .accounts
+        .values()
+        .map(|account| {
+            let addr = match &account.ty {
+                AccountType::Addr { addr } => Some(addr.clone()),
+                _ => account.addr.clone(),
+            };
+
+            vec![
+                account.name.clone(),
+                account.domain.get_chain_name().to_string(),
+                get_variant_name(&account.ty).unwrap_or_default(),
+                addr.unwrap_or_default(),
+            ]
+        })
+        .collect()
+}
+
+/// Replace account and library ids in a library config by their names
+fn format_config_value(program_config: &ProgramConfig, value: &Value) -> String {
+    if let Ok(account_type) = serde_json::from_value::<LibraryAccountType>(value.clone()) {
+        return match account_type {
+            LibraryAccountType::Addr(addr) => addr,
+            LibraryAccountType::AccountId(id) => program_config
+                .accounts
+                .get(&id)
+                .map(|account| format!("account {}", account.name))
+                .unwrap_or(format!("account {}", id)),
+            LibraryAccountType::LibraryId(id) => program_config
+                .libraries
+                .get(&id)
+                .map(|library| format!("library {}", library.name))
+                .unwrap_or(format!("library {}", id)),
+        };
+    }
+
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn library_rows(program_config: &ProgramConfig) -> Vec<Vec<String>> {
+    program_config
+        .libraries
+        .values()
+        .map(|library| {
+            // The config of the library is the only field of its variant
+            let fields = match serde_json::to_value(&library.config) {
+                Ok(Value::Object(variant)) => match variant.into_iter().next() {
+                    Some((_, Value::Object(fields))) => fields
+                        .iter()
+                        .map(|(key, value)| {
+                            format!("{}: {}", key, format_config_value(program_config, value))
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    _ => String::new(),
+                },
+                _ => String::new(),
+            };
+
+            vec![
+                library.name.clone(),
+                library.domain.get_chain_name().to_string(),
+                get_variant_name(&library.config).unwrap_or_default(),
+                fields,
+                library.addr.clone().unwrap_or_default(),
+            ]
+        })
+        .collect()
+}
+
+fn format_restriction(restriction: &ParamRestriction) -> String {
+    match restriction {
+        ParamRestriction::MustBeIncluded(path) => format!("must include {}", path.join(".")),
+        ParamRestriction::CannotBeIncluded(path) => format!("cannot include {}", path.join(".")),
+        ParamRestriction::MustBeValue(path, value) => format!(
+            "{} must be {}",
+            path.join("."),
+            serde_json::to_string(value).unwrap_or_default()
+        ),
+    }
+}
+
+fn authorization_rows(program_config: &ProgramConfig) -> Vec<Vec<String>> {
+    program_config
+        .authorizations
+        .iter()
+        .map(|authorization| {
+            let (mode, addrs) = match &authorization.mode {
+                AuthorizationModeInfo::Permissionless => ("permissionless", vec![]),
+                AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithCallLimit(addrs)) => (
+                    "permissioned with call limit",
+                    addrs
+                        .iter()
+                        .map(|(addr, limit)| format!("{} ({} calls)", addr, limit))
+                        .collect(),
+                ),
+                AuthorizationModeInfo::Permissioned(PermissionTypeInfo::WithoutCallLimit(
+                    addrs,
+                )) => ("permissioned without call limit", addrs.clone()),
+            };
+
+            let functions = get_authorization_functions(authorization);
+            let targets = functions
+                .iter()
+                .map(|function| {
+                    let target = match function.contract_address {
+                        LibraryAccountType::LibraryId(id) => program_config
+                            .libraries
+                            .get(id)
+                            .map(|library| library.name.clone())
+                            .unwrap_or(format!("library {}", id)),
+                        contract_address => {
+                            serde_json::to_string(contract_address).unwrap_or_default()
+                        }
+                    };
+
+                    format!(
+                        "{}.{} on {}",
+                        target,
+                        function.message_details.message.name,
+                        get_function_chain_name(function.domain)
+                    )
+                })
+                .collect::<Vec<_>>();
+            let restrictions = functions
+                .iter()
+                .flat_map(|function| {
+                    function
+                        .message_details
+                        .message
+                        .params_restrictions
+                        .iter()
+                        .flatten()
+                        .map(format_restriction)
+                })
+                .collect::<Vec<_>>();
+
+            vec![
+                authorization.label.clone(),
+                mode.to_string(),
+                addrs.join("\n"),
+                targets.join("\n"),
+                restrictions.join("\n"),
+            ]
+        })
+        .collect()
+}
+
+fn code_id_rows(program_config: &ProgramConfig, config: &Config) -> Vec<Vec<String>> {
+    let Ok(code_ids) = get_required_code_ids(program_config) else {
+        return vec![];
+    };
+
+    code_ids
+        .iter()
+        .flat_map(|(domain, keys)| {
+            keys.iter().map(move |key| {
+                let code_id = config
+                    .contracts
+                    .code_ids
+                    .get(domain)
+                    .and_then(|ids| ids.get(key))
+                    .map(|id| id.to_string())
+                    .unwrap_or("missing".to_string());
+
+                vec![domain.clone(), key.clone(), code_id]
+            })
+        })
+        .collect()
+}
+
+fn to_markdown(title: &str, tables: &[Table]) -> String {
+    let cell = |cell: &str| cell.replace('|', "\\|").replace('\n', "<br>");
+    let mut report = format!("# {}\n", title);
+
+    for table in tables {
+        report.push_str(&format!("\n## {}\n\n", table.title));
+        report.push_str(&format!("| {} |\n", table.headers.join(" | ")));
+        report.push_str(&format!("|{}\n", "---|".repeat(table.headers.len())));
+
+        for row in table.rows.iter() {
+            report.push_str(&format!(
+                "| {} |\n",
+                row.iter().map(|c| cell(c)).collect::<Vec<_>>().join(" | ")
+            ));
+        }
+    }
+
+    report
+}
+
+fn to_html(title: &str, tables: &[Table]) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let cell = |cell: &str| escape(cell).replace('\n', "<br>");
+
+    let mut report = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<h1>{0}</h1>\n",
+        escape(title)
+    );
+
+    for table in tables {
+        report.push_str(&format!("<h2>{}</h2>\n<table>\n<tr>", table.title));
+        for header in table.headers.iter() {
+            report.push_str(&format!("<th>{}</th>", header));
+        }
+        report.push_str("</tr>\n");
+
+        for row in table.rows.iter() {
+            report.push_str("<tr>");
+            for c in row {
+                report.push_str(&format!("<td>{}</td>", cell(c)));
+            }
+            report.push_str("</tr>\n");
+        }
+        report.push_str("</table>\n");
+    }
+
+    report.push_str("</body>\n</html>\n");
+
+    report
+}
@@ -0,0 +1,440 @@
+use cosmwasm_schema::schema_for;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use valence_authorization_utils::{
+    authorization::{AuthorizationModeInfo, PermissionTypeInfo},
+    authorization_message::ParamRestriction,
+};
+use valence_library_utils::LibraryAccountType;
+use valence_program_manager::program_config::ProgramConfig;
+
+use crate::{
+    invocation::decode_restriction_value,
+    messages::PROCESS_FUNCTION,
+    program_config::{get_authorization_functions, get_variant_name},
+};
+
+/// JSON schema of the `FunctionMsgs` of every library, keyed like the code ids
+fn get_library_function_schemas() -> BTreeMap<&'static str, Value> {
+    let schemas = [
+        (
+            "valence_forwarder_library",
+            schema_for!(valence_forwarder_library::msg::FunctionMsgs),
+        ),
+        (
+            "valence_splitter_library",
+            schema_for!(valence_splitter_library::msg::FunctionMsgs),
+        ),
+        (
+            "valence_reverse_splitter_library",
+            schema_for!(valence_reverse_splitter_library::msg::FunctionMsgs),
+        ),
+        (
+            "valence_astroport_lper",
+            schema_for!(valence_astroport_lper::msg::FunctionMsgs),
+        ),
+        (
+            "valence_astroport_withdrawer",
+            schema_for!(valence_astroport_withdrawer::msg::FunctionMsgs),
+        ),
+        (
+            "valence_generic_ibc_transfer_library",
+            schema_for!(valence_generic_ibc_transfer_library::msg::FunctionMsgs),
+        ),
+        (
+            "valence_neutron_ibc_transfer_library",
+            schema_for!(valence_neutron_ibc_transfer_library::msg::FunctionMsgs),
+        ),
+        (
+            "valence_osmosis_gamm_lper",
+            schema_for!(valence_osmosis_gamm_lper::msg::FunctionMsgs),
+        ),
+        (
+            "valence_osmosis_gamm_withdrawer",
+            schema_for!(valence_osmosis_gamm_withdrawer::msg::FunctionMsgs),
+        ),
+        (
+            "valence_osmosis_cl_lper",
+            schema_for!(valence_osmosis_cl_lper::msg::FunctionMsgs),
+        ),
+        (
+            "valence_osmosis_cl_withdrawer",
+            schema_for!(valence_osmosis_cl_withdrawer::msg::FunctionMsgs),
+        ),
+    ];
+
+    schemas
+        .into_iter()
+        .map(|(library, schema)| {
+            (
+                library,
+                serde_json::to_value(schema).expect("Failed to serialize library schema"),
+            )
+        })
+        .collect()
+}
+
+/// Check if we have the `FunctionMsgs` of a library, Example: `valence_forwarder_library`
+pub(crate) fn is_known_library(library: &str) -> bool {
+    get_library_function_schemas().contains_key(library)
+}
+
+/// Check if a path of fields can be part of a message of a JSON schema
+fn schema_has_path(root: &Value, schema: &Value, path: &[String]) -> bool {
+    // Definitions are referenced as `#/definitions/<name>`
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.rsplit('/').next().unwrap_or_default();
+        return schema_has_path(root, &root["definitions"][name], path);
+    }
+
+    let Some((key, rest)) = path.split_first() else {
+        return true;
+    };
+
+    let combined = ["oneOf", "anyOf", "allOf"].iter().any(|combinator| {
+        schema[combinator]
+            .as_array()
+            .is_some_and(|schemas| schemas.iter().any(|s| schema_has_path(root, s, path)))
+    });
+    if combined {
+        return true;
+    }
+
+    if let Some(property) = schema["properties"].get(key) {
+        return schema_has_path(root, property, rest);
+    }
+
+    // Unit variants are serialized as strings
+    if rest.is_empty()
+        && schema["enum"]
+            .as_array()
+            .is_some_and(|values| values.iter().any(|v| v.as_str() == Some(key)))
+    {
+        return true;
+    }
+
+    // Maps accept any key
+    match &schema["additionalProperties"] {
+        additional @ Value::Object(_) => schema_has_path(root, additional, rest),
+        _ => false,
+    }
+}
+
+/// Check that the paths of the restrictions of every function exist in the `FunctionMsgs` of its library
+pub fn check_restrictions_against_libraries(program_config: &ProgramConfig) -> Vec<String> {
+    let schemas = get_library_function_schemas();
+    let mut errors = vec![];
+
+    for authorization in program_config.authorizations.iter() {
+        for function in get_authorization_functions(authorization) {
+            let LibraryAccountType::LibraryId(id) = function.contract_address else {
+                continue;
+            };
+            let Some(library) = program_config.libraries.get(id) else {
+                continue;
+            };
+            // Libraries we don't know the messages of can't be checked
+            let Some(schema) = get_variant_name(&library.config)
+                .and_then(|library_type| schemas.get(library_type.as_str()))
+            else {
+                continue;
+            };
+
+            let message = &function.message_details.message;
+            if message.name != PROCESS_FUNCTION {
+                continue;
+            }
+
+            for restriction in message.params_restrictions.iter().flatten() {
+                let path = match restriction {
+                    ParamRestriction::MustBeIncluded(path)
+                    | ParamRestriction::MustBeValue(path, _) => path,
+                    ParamRestriction::CannotBeIncluded(_) => continue,
+                };
+
+                let valid = match path.split_first() {
+                    Some((first, rest)) if first == PROCESS_FUNCTION => {
+                        schema_has_path(schema, schema, rest)
+                    }
+                    _ => false,
+                };
+
+                if !valid {
+                    errors.push(format!(
+                        "authorization {}: restriction {} does not match any function of library {}",
+                        authorization.label,
+                        path.join("."),
+                        library.name
+                    ));
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+fn get_path<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, key| value.get(key))
+}
+
+/// Check offline if the authorization contract would accept the messages for an authorization,
+/// returns every reason it would be rejected
+pub fn evaluate_authorization(
+    program_config: &ProgramConfig,
+    label: &str,
+    messages: &Value,
+    sender: Option<&str>,
+) -> Result<(), Vec<String>> {
+    let authorization = program_config
+        .authorizations
+        .iter()
+        .find(|authorization| authorization.label == label)
+        .ok_or(vec![format!("Authorization {} not found", label)])?;
+
+    let mut reasons = vec![];
+
+    match (&authorization.mode, sender) {
+        (AuthorizationModeInfo::Permissionless, _) => (),
+        (AuthorizationModeInfo::Permissioned(_), None) => reasons.push(
+            "authorization is permissioned, pass the sender to check it is allowed".to_string(),
+        ),
+        (AuthorizationModeInfo::Permissioned(permission), Some(sender)) => {
+            let allowed = match permission {
+                PermissionTypeInfo::WithCallLimit(addrs) => {
+                    addrs.iter().any(|(addr, _)| addr == sender)
+                }
+                PermissionTypeInfo::WithoutCallLimit(addrs) => addrs.iter().any(|a| a == sender),
+            };
+
+            if !allowed {
+                reasons.push(format!("{} is not a permissioned address", sender));
+            }
+        }
+    }
+
+    // A single message can be given without an array
+    let messages = match messages {
+        Value::Array(messages) => messages.clone(),
+        message => vec![message.clone()],
+    };
+    let functions = get_authorization_functions(authorization);
+
+    if messages.len() != functions.len() {
+        reasons.push(format!(
+            "expected {} messages, got {}",
+            functions.len(),
+            messages.len()
+        ));
+    }
+
+    for (i, (function, message)) in functions.iter().zip(messages.iter()).enumerate() {
+        let details = &function.message_details.message;
+
+        if message.get(&details.name).is_none() {
+            reasons.push(format!(
+                "message {}: expected a {} message",
+                i, details.name
+            ));
+            continue;
+        }
+
+        for restriction in details.params_restrictions.iter().flatten() {
+            match restriction {
+                ParamRestriction::MustBeIncluded(path) => {
+                    if get_path(message, path).is_none() {
+                        reasons.push(format!(
+                            "message {}: {} must be included",
+                            i,
+                            path.join(".")
+                        ));
+                    }
+                }
+                ParamRestriction::CannotBeIncluded(path) => {
+                    if get_path(message, path).is_some() {
+                        reasons.push(format!(
+                            "message {}: {} cannot be included",
+                            i,
+                            path.join(".")
+                        ));
+                    }
+                }
+                ParamRestriction::MustBeValue(path, value) => {
+                    let expected = decode_restriction_value(value);
+                    if get_path(message, path) != Some(&expected) {
+                        reasons.push(format!(
+                            "message {}: {} must be {}",
+                            i,
+                            path.join("."),
+                            expected
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    if reasons.is_empty() {
+        Ok(())
+    } else {
+        Err(reasons)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use valence_authorization_utils::{
+        authorization::{AuthorizationModeInfo, PermissionTypeInfo},
+        authorization_message::{Message, MessageDetails, MessageType, ParamRestriction},
+        builders::{AtomicFunctionBuilder, AtomicSubroutineBuilder, AuthorizationBuilder},
+        domain::Domain,
+    };
+    use valence_library_utils::LibraryAccountType;
+    use valence_program_manager::{
+        program_config::ProgramConfig, program_config_builder::ProgramConfigBuilder,
+    };
+
+    use super::evaluate_authorization;
+
+    const OWNER: &str = "neutron14wey3cpz2cxswu9u6gaalz2xxh03xdeyqal9cf";
+    const LIBRARY: &str = "neutron1qx9qkzs2ga7xvmqlnrlzd0jyw5cm5h6qk6cpkq";
+
+    fn path(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    /// A `forward` authorization that can't set the `amount` of the forward
+    fn program_config(mode: AuthorizationModeInfo) -> ProgramConfig {
+        let message = MessageDetails {
+            message_type: MessageType::CosmwasmExecuteMsg,
+            message: Message {
+                name: "process_function".to_string(),
+                params_restrictions: Some(vec![
+                    ParamRestriction::MustBeIncluded(path(&["process_function", "forward"])),
+                    ParamRestriction::CannotBeIncluded(path(&[
+                        "process_function",
+                        "forward",
+                        "amount",
+                    ])),
+                ]),
+            },
+        };
+
+        let mut builder = ProgramConfigBuilder::new("test", OWNER);
+        builder.add_authorization(
+            AuthorizationBuilder::new()
+                .with_label("forward")
+                .with_mode(mode)
+                .with_subroutine(
+                    AtomicSubroutineBuilder::new()
+                        .with_function(
+                            AtomicFunctionBuilder::new()
+                                .with_contract_address(LibraryAccountType::Addr(
+                                    LIBRARY.to_string(),
+                                ))
+                                .with_domain(Domain::Main)
+                                .with_message_details(message)
+                                .build(),
+                        )
+                        .build(),
+                )
+                .build(),
+        );
+
+        builder.build()
+    }
+
+    #[test]
+    fn accepts_message_that_satisfies_the_restrictions() {
+        let program_config = program_config(AuthorizationModeInfo::Permissionless);
+        let message = json!({"process_function": {"forward": {}}});
+
+        assert_eq!(
+            evaluate_authorization(&program_config, "forward", &message, None),
+            Ok(())
+        );
+        assert_eq!(
+            evaluate_authorization(&program_config, "forward", &json!([message]), None),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn rejects_message_that_breaks_the_restrictions() {
+        let program_config = program_config(AuthorizationModeInfo::Permissionless);
+
+        assert_eq!(
+            evaluate_authorization(
+                &program_config,
+                "forward",
+                &json!({"process_function": {"split": {}}}),
+                None
+            ),
+            Err(vec![
+                "message 0: process_function.forward must be included".to_string()
+            ])
+        );
+        assert_eq!(
+            evaluate_authorization(
+                &program_config,
+                "forward",
+                &json!({"process_function": {"forward": {"amount": "1"}}}),
+                None
+            ),
+            Err(vec![
+                "message 0: process_function.forward.amount cannot be included".to_string()
+            ])
+        );
+        assert_eq!(
+            evaluate_authorization(&program_config, "forward", &json!({"execute": {}}), None),
+            Err(vec![
+                "message 0: expected a process_function message".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_number_of_messages() {
+        let program_config = program_config(AuthorizationModeInfo::Permissionless);
+        let message = json!({"process_function": {"forward": {}}});
+
+        assert_eq!(
+            evaluate_authorization(&program_config, "forward", &json!([message, message]), None),
+            Err(vec!["expected 1 messages, got 2".to_string()])
+        );
+    }
+
+    #[test]
+    fn checks_the_sender_of_permissioned_authorizations() {
+        let program_config = program_config(AuthorizationModeInfo::Permissioned(
+            PermissionTypeInfo::WithoutCallLimit(vec![OWNER.to_string()]),
+        ));
+        let message = json!({"process_function": {"forward": {}}});
+
+        assert_eq!(
+            evaluate_authorization(&program_config, "forward", &message, Some(OWNER)),
+            Ok(())
+        );
+        assert_eq!(
+            evaluate_authorization(&program_config, "forward", &message, Some(LIBRARY)),
+            Err(vec![format!("{} is not a permissioned address", LIBRARY)])
+        );
+        assert_eq!(
+            evaluate_authorization(&program_config, "forward", &message, None),
+            Err(vec![
+                "authorization is permissioned, pass the sender to check it is allowed".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_label() {
+        let program_config = program_config(AuthorizationModeInfo::Permissionless);
+
+        assert_eq!(
+            evaluate_authorization(&program_config, "withdraw", &json!({}), None),
+            Err(vec!["Authorization withdraw not found".to_string()])
+        );
+    }
+}
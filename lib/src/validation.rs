@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use valence_library_utils::LibraryAccountType;
+use valence_program_manager::program_config::ProgramConfig;
+
+use crate::program_config::{get_authorization_functions, get_function_chain_name};
+
+/// Check the structure of a program config before it is deployed,
+/// returns every problem found with the labels of what is wrong
+pub fn validate_program_config(program_config: &ProgramConfig) -> Vec<String> {
+    let mut errors = vec![];
+
+    // Links must use accounts and libraries that exist
+    for link in program_config.links.values() {
+        if !program_config.libraries.contains_key(&link.library_id) {
+            errors.push(format!(
+                "link uses library id {} that does not exist",
+                link.library_id
+            ));
+        }
+        for account_id in link
+            .input_accounts_id
+            .iter()
+            .chain(link.output_accounts_id.iter())
+        {
+            if !program_config.accounts.contains_key(account_id) {
+                errors.push(format!(
+                    "link uses account id {} that does not exist",
+                    account_id
+                ));
+            }
+        }
+    }
+
+    // Authorization labels must be unique
+    let mut labels: BTreeMap<&str, usize> = BTreeMap::new();
+    for authorization in program_config.authorizations.iter() {
+        *labels.entry(authorization.label.as_str()).or_default() += 1;
+    }
+    for (label, count) in labels.into_iter().filter(|(_, count)| *count > 1) {
+        errors.push(format!(
+            "authorization {}: label is used {} times",
+            label, count
+        ));
+    }
+
+    // Every function must target a library of the program on the library domain
+    let mut authorized_libraries = vec![];
+    for authorization in program_config.authorizations.iter() {
+        for function in get_authorization_functions(authorization) {
+            match function.contract_address {
+                LibraryAccountType::LibraryId(id) => match program_config.libraries.get(id) {
+                    Some(library) => {
+                        authorized_libraries.push(*id);
+
+                        let function_chain = get_function_chain_name(function.domain);
+                        let library_chain = library.domain.get_chain_name();
+                        if function_chain != library_chain {
+                            errors.push(format!(
+                                "authorization {}: function domain {} does not match the domain {} of library {}",
+                                authorization.label, function_chain, library_chain, library.name
+                            ));
+                        }
+                    }
+                    None => errors.push(format!(
+                        "authorization {}: function targets library id {} that does not exist",
+                        authorization.label, id
+                    )),
+                },
+                LibraryAccountType::AccountId(id) => errors.push(format!(
+                    "authorization {}: function targets account id {} instead of a library",
+                    authorization.label, id
+                )),
+                // External contracts can't be checked
+                LibraryAccountType::Addr(_) => (),
+            }
+        }
+    }
+
+    // Every library must be linked and authorized, otherwise it can never be executed
+    for (id, library) in program_config.libraries.iter() {
+        if !program_config
+            .links
+            .values()
+            .any(|link| link.library_id == *id)
+        {
+            errors.push(format!("library {}: not used by any link", library.name));
+        }
+        if !authorized_libraries.contains(id) {
+            errors.push(format!(
+                "library {}: not used by any authorization",
+                library.name
+            ));
+        }
+    }
+
+    // Every account must be connected to a library
+    for (id, account) in program_config.accounts.iter() {
+        let connected = program_config.links.values().any(|link| {
+            link.input_accounts_id.contains(id) || link.output_accounts_id.contains(id)
+        });
+
+        if !connected {
+            errors.push(format!(
+                "account {}: not connected to any library",
+                account.name
+            ));
+        }
+    }
+
+    errors
+}
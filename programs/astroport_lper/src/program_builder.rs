@@ -1,10 +1,9 @@
-use valence_astroport_lper::msg::LiquidityProviderConfig;
+use deployer_lib::{ add_library, authorize };
+use valence_astroport_lper::msg::{ FunctionMsgs as LperFunctionMsgs, LiquidityProviderConfig };
 use valence_astroport_utils::PoolType;
-use valence_astroport_withdrawer::msg::LiquidityWithdrawerConfig;
-use valence_authorization_utils::{
-    authorization::{ AuthorizationModeInfo, PermissionTypeInfo },
-    authorization_message::{ Message, MessageDetails, MessageType, ParamRestriction },
-    builders::{ AtomicFunctionBuilder, AtomicSubroutineBuilder, AuthorizationBuilder },
+use valence_astroport_withdrawer::msg::{
+    FunctionMsgs as WithdrawerFunctionMsgs,
+    LiquidityWithdrawerConfig,
 };
 use valence_library_utils::liquidity_utils::AssetData;
 use valence_program_manager::{
@@ -52,7 +51,8 @@ pub fn program_builder(params: deployer_lib::ProgramParams) -> ProgramConfig {
             "concentrated".to_string()
         )
     );
-    let liquidity_provider_library = builder.add_library(
+    let liquidity_provider_library = add_library(
+        &mut builder,
         LibraryInfo::new(
             "deploy_liquidity".to_string(),
             &neutron_domain,
@@ -72,7 +72,8 @@ pub fn program_builder(params: deployer_lib::ProgramParams) -> ProgramConfig {
         )
     );
 
-    let liquidity_withdrawer_library = builder.add_library(
+    let liquidity_withdrawer_library = add_library(
+        &mut builder,
         LibraryInfo::new(
             "withdraw_liquidity_position".to_string(),
             &neutron_domain,
@@ -106,65 +107,26 @@ pub fn program_builder(params: deployer_lib::ProgramParams) -> ProgramConfig {
     );
 
     // Authorizations
-    let provide_liqudity_function = AtomicFunctionBuilder::new()
-        .with_contract_address(liquidity_provider_library.clone())
-        .with_message_details(MessageDetails {
-            message_type: MessageType::CosmwasmExecuteMsg,
-            message: Message {
-                name: "process_function".to_string(),
-                params_restrictions: Some(
-                    vec![
-                        ParamRestriction::MustBeIncluded(
-                            vec![
-                                "process_function".to_string(),
-                                "provide_double_sided_liquidity".to_string()
-                            ]
-                        )
-                    ]
-                ),
-            },
-        })
-        .build();
-
-    builder.add_authorization(
-        AuthorizationBuilder::new()
-            .with_label("provide_liquidity")
-            .with_subroutine(
-                AtomicSubroutineBuilder::new().with_function(provide_liqudity_function).build()
-            )
-            .build()
-    );
-
-    let withdraw_liqudity_function = AtomicFunctionBuilder::new()
-        .with_contract_address(liquidity_withdrawer_library.clone())
-        .with_message_details(MessageDetails {
-            message_type: MessageType::CosmwasmExecuteMsg,
-            message: Message {
-                name: "process_function".to_string(),
-                params_restrictions: Some(
-                    vec![
-                        ParamRestriction::MustBeIncluded(
-                            vec!["process_function".to_string(), "withdraw_liquidity".to_string()]
-                        )
-                    ]
-                ),
-            },
-        })
-        .build();
+    authorize(
+        &mut builder,
+        "provide_liquidity",
+        &liquidity_provider_library,
+        LperFunctionMsgs::ProvideDoubleSidedLiquidity {
+            expected_pool_ratio_range: None,
+        }
+    )
+        .add()
+        .expect("Failed to add the provide_liquidity authorization");
 
-    builder.add_authorization(
-        AuthorizationBuilder::new()
-            .with_mode(
-                AuthorizationModeInfo::Permissioned(
-                    PermissionTypeInfo::WithoutCallLimit(vec![permissioned_withdrawer])
-                )
-            )
-            .with_label("withdraw_liquidity")
-            .with_subroutine(
-                AtomicSubroutineBuilder::new().with_function(withdraw_liqudity_function).build()
-            )
-            .build()
-    );
+    authorize(
+        &mut builder,
+        "withdraw_liquidity",
+        &liquidity_withdrawer_library,
+        WithdrawerFunctionMsgs::WithdrawLiquidity {}
+    )
+        .permissioned(vec![permissioned_withdrawer])
+        .add()
+        .expect("Failed to add the withdraw_liquidity authorization");
 
     builder.build()
 }
@@ -1,7 +1,4 @@
-use valence_authorization_utils::{
-    authorization_message::{Message, MessageDetails, MessageType, ParamRestriction},
-    builders::{AtomicFunctionBuilder, AtomicSubroutineBuilder, AuthorizationBuilder},
-};
+use deployer_lib::{add_library, authorize};
 use valence_library_utils::denoms::UncheckedDenom;
 use valence_program_manager::{
     account::{AccountInfo, AccountType},
@@ -9,19 +6,18 @@ use valence_program_manager::{
     program_config::ProgramConfig,
     program_config_builder::ProgramConfigBuilder,
 };
-use valence_splitter_library::msg::{UncheckedSplitAmount, UncheckedSplitConfig};
+use valence_splitter_library::msg::{FunctionMsgs, UncheckedSplitAmount, UncheckedSplitConfig};
 
 /// Write your program using the program builder
 pub fn program_builder(params: deployer_lib::ProgramParams) -> ProgramConfig {
     // program params
     let owner = params.get("owner");
+    let (swap_denom, swap_amount) = params.get_coin("swap_amount", "neutron");
 
     // Domains
     let neutron_domain = valence_program_manager::domain::Domain::CosmosCosmwasm("neutron".to_string());
 
     // Write your program
-    let swap_amount: u128 = 1_000_000_000;
-
     let mut builder = ProgramConfigBuilder::new("test_program", &owner);
 
     let account_1 = builder.add_account(AccountInfo::new(
@@ -38,13 +34,13 @@ pub fn program_builder(params: deployer_lib::ProgramParams) -> ProgramConfig {
     let library_config = valence_splitter_library::msg::LibraryConfig {
         input_addr: account_1.clone(),
         splits: vec![UncheckedSplitConfig {
-            denom: UncheckedDenom::Native("untrn".to_string()),
+            denom: UncheckedDenom::Native(swap_denom),
             account: account_2.clone(),
             amount: UncheckedSplitAmount::FixedAmount(swap_amount.into()),
         }],
     };
 
-    let library_1 = builder.add_library(LibraryInfo::new(
+    let library_1 = add_library(&mut builder, LibraryInfo::new(
         "test_splitter".to_string(),
         &neutron_domain,
         LibraryConfig::ValenceSplitterLibrary(library_config.clone()),
@@ -52,31 +48,9 @@ pub fn program_builder(params: deployer_lib::ProgramParams) -> ProgramConfig {
 
     builder.add_link(&library_1, vec![&account_1], vec![&account_2]);
 
-    let action_label = "swap";
-
-    let function = AtomicFunctionBuilder::new()
-        .with_contract_address(library_1.clone())
-        .with_message_details(MessageDetails {
-            message_type: MessageType::CosmwasmExecuteMsg,
-            message: Message {
-                name: "process_function".to_string(),
-                params_restrictions: Some(vec![ParamRestriction::MustBeIncluded(vec![
-                    "process_function".to_string(),
-                    "split".to_string(),
-                ])]),
-            },
-        })
-        .build();
-
-    let subroutine = AtomicSubroutineBuilder::new()
-        .with_function(function)
-        .build();
-    let authorization = AuthorizationBuilder::new()
-        .with_label(action_label)
-        .with_subroutine(subroutine)
-        .build();
-
-    builder.add_authorization(authorization);
+    authorize(&mut builder, "swap", &library_1, FunctionMsgs::Split {})
+        .add()
+        .expect("Failed to add the swap authorization");
 
     builder.build()
 }